@@ -32,4 +32,258 @@ create table if not exists `uv_lamp_mqtt_notify_jobs`(
     `updated_at` timestamp not null default current_timestamp on update current_timestamp comment '更新时间'
 ) comment '紫外线等 MQTT 通知任务表';
 
-alter table `uv_lamp_mqtt_notify_jobs` add column `type` varchar(64) not null default 'LIGHT_SWITCH_TASK' comment '任务' after `next_retry_time`;
\ No newline at end of file
+alter table `uv_lamp_mqtt_notify_jobs` add column `type` varchar(64) not null default 'LIGHT_SWITCH_TASK' comment '任务' after `next_retry_time`;
+
+create table if not exists `uv_lamp_devices`(
+    `id` bigint unsigned auto_increment not null primary key comment '主键',
+    `device_number` varchar(128) not null comment '设备编号',
+    `name` varchar(128) not null default '' comment '设备名称',
+    `remark` varchar(512) not null default '' comment '备注',
+    `is_decommissioned` tinyint unsigned not null default 0 comment '是否停用:0-在用;1-停用',
+    `decommissioned_at` timestamp null comment '停用时间',
+    `created_at` timestamp not null default current_timestamp comment '创建时间',
+    `updated_at` timestamp not null default current_timestamp on update current_timestamp comment '更新时间',
+    unique key `uk_device_number` (`device_number`)
+) comment '紫外线灯设备表';
+
+-- 原 `cron::DEVICE_NUMBERS` 中硬编码的设备
+insert ignore into `uv_lamp_devices` (`device_number`) values
+    ('012005202000093'),
+    ('012005202000081'),
+    ('012101303000024'),
+    ('012101303000023'),
+    ('012101303000022'),
+    ('012101303000021'),
+    ('012101303000020'),
+    ('012101303000019'),
+    ('012101303000018'),
+    ('012101303000017'),
+    ('012101303000016'),
+    ('012101303000015'),
+    ('012101303000013'),
+    ('012101303000012'),
+    ('012101303000011'),
+    ('012101303000010'),
+    ('012101303000009'),
+    ('012101303000005'),
+    ('012101303000224'),
+    ('012101303000223'),
+    ('012101303000222'),
+    ('012101303000221'),
+    ('012101303000220'),
+    ('012101303000219'),
+    ('012101303000218'),
+    ('012101303000217'),
+    ('012101303000216'),
+    ('012101303000215'),
+    ('012101303000214'),
+    ('012101303000213'),
+    ('012101303000212'),
+    ('012101303000211'),
+    ('012101303000209'),
+    ('012101303000208'),
+    ('012101303000207'),
+    ('012101303000205'),
+    ('012101303000064'),
+    ('012101303000063'),
+    ('012101303000062'),
+    ('012101303000061'),
+    ('012101303000060'),
+    ('012101303000059'),
+    ('012101303000056'),
+    ('012101303000055'),
+    ('012101303000054'),
+    ('012101303000053'),
+    ('012101303000052'),
+    ('012101303000051'),
+    ('012101303000050'),
+    ('012101303000049'),
+    ('012101303000048'),
+    ('012101303000046'),
+    ('012101303000045'),
+    ('012101303000243'),
+    ('012101303000240'),
+    ('012101303000239'),
+    ('012101303000238'),
+    ('012101303000237'),
+    ('012101303000235'),
+    ('012101303000234'),
+    ('012101303000233'),
+    ('012101303000232'),
+    ('012101303000230'),
+    ('012101303000229'),
+    ('012101303000228'),
+    ('012101303000227'),
+    ('012101303000225'),
+    ('012101303000404'),
+    ('012101303000402'),
+    ('012101303000401'),
+    ('012101303000399'),
+    ('012101303000398'),
+    ('012101303000397'),
+    ('012101303000395'),
+    ('012101303000394'),
+    ('012101303000392'),
+    ('012101303000393'),
+    ('012101303000391'),
+    ('012101303000386'),
+    ('012101303000389'),
+    ('012101303000388'),
+    ('012101303000308'),
+    ('012101303000307'),
+    ('012101303000306'),
+    ('012101303000312'),
+    ('012101303000311'),
+    ('012101303000310'),
+    ('012101303000313'),
+    ('012101303000314'),
+    ('012101303000315'),
+    ('012101303000309'),
+    ('012101303000321'),
+    ('012101303000320'),
+    ('012101303000319'),
+    ('012101303000318'),
+    ('012101303000323'),
+    ('012101303000324'),
+    ('012101303000383'),
+    ('012101303000381'),
+    ('012005202000435'),
+    ('012101303000374'),
+    ('012101303000367'),
+    ('012101303000369'),
+    ('012101303000370'),
+    ('012101303000384'),
+    ('012101303000325'),
+    ('012101303000326'),
+    ('012101303000327'),
+    ('012101303000331'),
+    ('012101303000333'),
+    ('012101303000329'),
+    ('012101303000330'),
+    ('012101303000489'),
+    ('012101303000334'),
+    ('012101303000332'),
+    ('012101303000335'),
+    ('012101303000336'),
+    ('012101303000496'),
+    ('012101303000498'),
+    ('012101303000340'),
+    ('012101303000339');
+
+alter table `uv_lamp_mqtt_messages` modify column `created_at` timestamp(3) not null default current_timestamp(3) comment '创建时间';
+alter table `uv_lamp_mqtt_messages` add column `acked_at` timestamp(3) null comment '确认时间' after `is_acked`;
+alter table `uv_lamp_mqtt_messages` add column `ack_latency_ms` int unsigned null comment '确认耗时:毫秒' after `acked_at`;
+alter table `uv_lamp_mqtt_messages` add index `idx_device_number_message_id` (`device_number`, `message_id`);
+
+create table if not exists `uv_lamp_device_groups`(
+    `id` bigint unsigned auto_increment not null primary key comment '主键',
+    `parent_id` bigint unsigned not null default 0 comment '上级分组ID:0-顶级分组',
+    `name` varchar(128) not null comment '分组名称',
+    `kind` varchar(32) not null default 'ZONE' comment '分组类型:BUILDING-楼栋;FLOOR-楼层;WARD-病区;ROOM-房间;ZONE-区域',
+    `remark` varchar(512) not null default '' comment '备注',
+    `deleted_at` timestamp null comment '删除时间',
+    `created_at` timestamp not null default current_timestamp comment '创建时间',
+    `updated_at` timestamp not null default current_timestamp on update current_timestamp comment '更新时间',
+    key `idx_parent_id` (`parent_id`)
+) comment '紫外线灯设备分组表';
+
+create table if not exists `uv_lamp_device_group_members`(
+    `id` bigint unsigned auto_increment not null primary key comment '主键',
+    `group_id` bigint unsigned not null comment '分组ID',
+    `device_number` varchar(128) not null comment '设备编号',
+    `created_at` timestamp not null default current_timestamp comment '创建时间',
+    unique key `uk_group_id_device_number` (`group_id`, `device_number`)
+) comment '紫外线灯设备分组成员表';
+
+create table if not exists `uv_lamp_schedules`(
+    `id` bigint unsigned auto_increment not null primary key comment '主键',
+    `name` varchar(128) not null comment '计划名称',
+    `cron_expression` varchar(128) not null default '' comment 'Cron 表达式(秒 分 时 日 月 周 [年])，为空时使用每周日历',
+    `weekdays` varchar(32) not null default '' comment '每周日历:执行的星期，如 MON,WED,FRI',
+    `time_of_day` varchar(8) not null default '' comment '每周日历:执行时间，如 07:30',
+    `device_number` varchar(128) not null default '' comment '目标设备编号',
+    `group_id` bigint unsigned not null default 0 comment '目标分组ID:0-未指定',
+    `status` tinyint unsigned not null default 1 comment '指令:0-关闭;1-打开',
+    `duration` int not null default 0 comment '消毒时间:分钟',
+    `timezone` varchar(64) not null default '' comment '时区，为空时使用服务默认时区',
+    `is_enabled` tinyint unsigned not null default 1 comment '是否启用:0-停用;1-启用',
+    `last_run_at` timestamp null comment '最近执行时间',
+    `deleted_at` timestamp null comment '删除时间',
+    `created_at` timestamp not null default current_timestamp comment '创建时间',
+    `updated_at` timestamp not null default current_timestamp on update current_timestamp comment '更新时间'
+) comment '紫外线灯消毒计划表';
+
+create table if not exists `users`(
+    `id` bigint unsigned auto_increment not null primary key comment '主键',
+    `username` varchar(64) not null comment '用户名',
+    `password_hash` varchar(255) not null comment '密码哈希(argon2)',
+    `is_disabled` tinyint unsigned not null default 0 comment '是否禁用:0-正常;1-禁用',
+    `last_login_at` timestamp null comment '最近登录时间',
+    `deleted_at` timestamp null comment '删除时间',
+    `created_at` timestamp not null default current_timestamp comment '创建时间',
+    `updated_at` timestamp not null default current_timestamp on update current_timestamp comment '更新时间',
+    unique key `uk_username` (`username`)
+) comment '用户表';
+
+create table if not exists `api_keys`(
+    `id` bigint unsigned auto_increment not null primary key comment '主键',
+    `name` varchar(128) not null comment '名称，用于区分调用方',
+    `key_prefix` varchar(16) not null comment '密钥前缀，用于查找密钥',
+    `key_hash` varchar(255) not null comment '密钥哈希(argon2)',
+    `created_by` bigint unsigned not null default 0 comment '创建人用户ID',
+    `last_used_at` timestamp null comment '最近使用时间',
+    `expires_at` timestamp null comment '过期时间，为空表示永不过期',
+    `revoked_at` timestamp null comment '吊销时间',
+    `created_at` timestamp not null default current_timestamp comment '创建时间',
+    `updated_at` timestamp not null default current_timestamp on update current_timestamp comment '更新时间',
+    unique key `uk_key_prefix` (`key_prefix`)
+) comment 'API 密钥表';
+
+alter table `users` add column `role` varchar(16) not null default 'VIEWER' comment '角色:VIEWER-只读;OPERATOR-操作员;ADMIN-管理员' after `password_hash`;
+alter table `api_keys` add column `role` varchar(16) not null default 'OPERATOR' comment '角色:VIEWER-只读;OPERATOR-操作员;ADMIN-管理员' after `key_hash`;
+
+alter table `uv_lamp_mqtt_received_messages` add index `idx_device_number_created_at` (`device_number`, `created_at`);
+alter table `uv_lamp_mqtt_received_messages` add index `idx_created_at` (`created_at`);
+
+alter table `uv_lamp_mqtt_notify_jobs` add index `idx_is_completed_type` (`is_completed`, `type`);
+
+create table if not exists `uv_lamp_mqtt_notify_job_attempts`(
+    `id` bigint unsigned auto_increment not null primary key comment '主键',
+    `job_id` bigint unsigned not null comment '通知任务ID',
+    `attempt` tinyint unsigned not null comment '第几次发送:从 1 开始',
+    `is_success` tinyint unsigned not null default 0 comment '是否成功:0-否;1-是',
+    `status_code` smallint unsigned null comment '回调接口返回的 HTTP 状态码',
+    `error` varchar(512) not null default '' comment '失败原因',
+    `created_at` timestamp not null default current_timestamp comment '创建时间',
+    index `idx_job_id` (`job_id`)
+) comment '紫外线灯 MQTT 通知任务发送记录表';
+
+alter table `uv_lamp_mqtt_messages` add column `request_id` varchar(64) null comment '下发指令的接口请求ID' after `payload`;
+alter table `uv_lamp_mqtt_notify_jobs` add column `request_id` varchar(64) null comment '触发通知的指令对应的接口请求ID' after `type`;
+
+create table if not exists `idempotency_keys`(
+    `id` bigint unsigned auto_increment not null primary key comment '主键',
+    `path` varchar(128) not null comment '接口路径',
+    `idempotency_key` varchar(128) not null comment '客户端传入的 Idempotency-Key, 未传时为设备编号和消息ID',
+    `fingerprint` char(32) not null comment '请求参数的 MD5, 同一个 key 参数不同时拒绝',
+    `status_code` smallint unsigned null comment '首次请求的 HTTP 状态码, 为空表示处理中',
+    `response` text null comment '首次请求的响应内容',
+    `expires_at` timestamp not null comment '过期时间, 过期后同一个 key 视为新请求',
+    `created_at` timestamp not null default current_timestamp comment '创建时间',
+    `updated_at` timestamp not null default current_timestamp on update current_timestamp comment '更新时间',
+    unique key `uk_path_idempotency_key` (`path`, `idempotency_key`),
+    key `idx_expires_at` (`expires_at`)
+) comment '接口幂等记录表';
+
+alter table `uv_lamp_devices` add column `product_key` varchar(64) not null default '' comment '设备所属产品, 为空时使用默认产品' after `remark`;
+alter table `idempotency_keys` add column `locked_until` timestamp not null default current_timestamp comment '处理中记录的租约到期时间, 处理期间定时续期, 到期仍未完成视为进程中断' after `response`;
+alter table `idempotency_keys` add column `principal` varchar(64) not null default '' comment '调用方, 如 user:1、api_key:1, 不同调用方的 key 互不影响' after `id`,
+    drop index `uk_path_idempotency_key`,
+    add unique key `uk_principal_path_idempotency_key` (`principal`, `path`, `idempotency_key`);
+
+alter table `uv_lamp_mqtt_received_messages` add column `kind` varchar(8) not null default '' comment '主题通道的类型段:oc/up/nI, 无法识别时为空' after `topic`,
+    add index `idx_kind_created_at` (`kind`, `created_at`);
+-- 历史数据按默认主题模板 {product_key}/{device_number}/{channel} 回填
+update `uv_lamp_mqtt_received_messages` set `kind` = substring_index(substring_index(`topic`, '/', 3), '/', -1) where `kind` = '' and substring_index(substring_index(`topic`, '/', 3), '/', -1) in ('oc', 'up', 'nI');
+
+alter table `uv_lamp_mqtt_notify_job_attempts` modify column `attempt` smallint unsigned not null comment '第几次发送:从 1 开始, 手动重试后继续递增';
@@ -1,26 +1,52 @@
-use crate::cron::DEVICE_NUMBERS;
+use crate::protocol::common::MessageId;
+use crate::protocol::network::NetworkQuery;
+use crate::repositories::uv_lamp_devices::UVLampDevice;
 use crate::utils;
 use futures::future::BoxFuture;
 use futures::FutureExt;
 use rand::Rng;
 use rand_core::SeedableRng;
-use serde_json::json;
 use std::time::{SystemTime, UNIX_EPOCH};
 use tracing::error;
 use crate::utils::mqtt::get_device_manager;
+use crate::utils::topic::{get_topic_config, CHANNEL_NETWORK_QUERY};
 
 pub fn handle() -> BoxFuture<'static, ()> {
     async move {
-        let index = get_device_number();
-        let device_number = DEVICE_NUMBERS[index];
-        let topic = get_topic(&device_number);
+        // 每次都从设备注册表读取，新增或停用的设备无需重启即可生效
+        let devices = match UVLampDevice::find_all(false).await {
+            Ok(devices) => devices,
+            Err(e) => {
+                error!("Failed to load registered devices: {}", e);
+                return;
+            }
+        };
+        if devices.is_empty() {
+            return;
+        }
+        let device_numbers: Vec<String> = devices
+            .iter()
+            .map(|device| device.device_number.clone())
+            .collect();
+        get_device_manager()
+            .lock()
+            .await
+            .retain_devices(&device_numbers);
+
+        let device = &devices[get_device_number(devices.len())];
+        let device_number = &device.device_number;
+        let topic = get_topic_config().topic(
+            &device.product_key,
+            device_number,
+            CHANNEL_NETWORK_QUERY,
+        );
 
         let mut rng = rand::rngs::StdRng::from_entropy();
         let random_number: u32 = rng.gen_range(100_000..1_000_000);
-        let message = json!({
-            "id": random_number.to_string(),
+        let message = serde_json::to_string(&NetworkQuery {
+            id: MessageId::Text(random_number.to_string()),
         })
-        .to_string();
+        .expect("Failed to serialize network query");
         if let Some(mqtt_handler) = utils::mqtt::instance() {
             mqtt_handler
                 .send(topic.as_str(), message.clone())
@@ -30,7 +56,7 @@ pub fn handle() -> BoxFuture<'static, ()> {
             // 在内存中维护设备的在线状态
             let manager = get_device_manager();
             let mut manager = manager.lock().await;
-            manager.record_query_time(&device_number.to_string());
+            manager.record_query_time(device_number);
         } else {
             error!("MQTT Handler not initialized");
         }
@@ -38,17 +64,12 @@ pub fn handle() -> BoxFuture<'static, ()> {
     .boxed()
 }
 
-fn get_device_number() -> usize {
+fn get_device_number(device_count: usize) -> usize {
     let start = SystemTime::now();
     let since_the_epoch = start
         .duration_since(UNIX_EPOCH)
         .expect("Time went backwards");
     let timestamp = since_the_epoch.as_secs();
 
-    (timestamp % DEVICE_NUMBERS.len() as u64) as usize
-}
-
-fn get_topic(device_number: &str) -> String {
-    let topic = format!("87855294541367dab3e244c2441c5f22/{}/nI/s", device_number);
-    topic
+    (timestamp % device_count as u64) as usize
 }
@@ -1,11 +1,17 @@
 use chrono::Utc;
+use chrono_tz::Tz;
+use crate::utils::metrics::CRON_TASK_DURATION_SECONDS;
 use cron::Schedule;
 use futures::future::BoxFuture;
+use metrics::histogram;
+use once_cell::sync::OnceCell;
 use std::collections::HashMap;
 use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
 use tokio::time;
 use tracing::log::error;
 use tracing::{debug, info};
@@ -15,56 +21,192 @@ type Task = Arc<dyn Fn() -> BoxFuture<'static, ()> + Send + Sync>;
 #[derive(Clone)]
 struct CronTask {
     schedule: Schedule,
+    timezone: Tz,
     task: Task,
 }
 
+struct CronHandle {
+    handle: JoinHandle<()>,
+    // 调度表中已没有下一次执行时间，循环正常结束
+    completed: Arc<AtomicBool>,
+}
+
 pub struct CronTaskManager {
     tasks: Arc<Mutex<HashMap<String, CronTask>>>,
+    handles: Arc<Mutex<HashMap<String, CronHandle>>>,
+    started: AtomicBool,
+    timezone: Tz,
+}
+
+impl Default for CronTaskManager {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl CronTaskManager {
     pub fn new() -> Self {
+        Self::with_timezone(Tz::UTC)
+    }
+
+    /// `timezone` 是未单独指定时区的任务所使用的默认时区
+    pub fn with_timezone(timezone: Tz) -> Self {
         CronTaskManager {
             tasks: Arc::new(Mutex::new(HashMap::new())),
+            handles: Arc::new(Mutex::new(HashMap::new())),
+            started: AtomicBool::new(false),
+            timezone,
         }
     }
 
+    pub fn timezone(&self) -> Tz {
+        self.timezone
+    }
+
     pub async fn register_task(&self, name: String, cron_expression: &str, task: Task) {
-        let schedule = Schedule::from_str(cron_expression)
+        self.register_task_with_timezone(name, cron_expression, self.timezone, task)
+            .await
             .map_err(|e| {
                 error!("Failed to parse cron expression: {}", e);
                 e
             })
             .unwrap();
+    }
+
+    /// 注册任务，同名任务会被替换；如果管理器已经启动，任务立即开始调度
+    pub async fn register_task_with_timezone(
+        &self,
+        name: String,
+        cron_expression: &str,
+        timezone: Tz,
+        task: Task,
+    ) -> Result<(), anyhow::Error> {
+        let schedule = Schedule::from_str(cron_expression)?;
+        let cron_task = CronTask {
+            schedule,
+            timezone,
+            task,
+        };
         let mut tasks = self.tasks.lock().await;
         info!("Registering task '{}'", name);
-        tasks.insert(name, CronTask { schedule, task });
+        tasks.insert(name.clone(), cron_task.clone());
+
+        if self.started.load(Ordering::SeqCst) {
+            self.spawn(name, cron_task).await;
+        }
+        Ok(())
+    }
+
+    pub async fn remove_task(&self, name: &str) {
+        if self.tasks.lock().await.remove(name).is_some() {
+            info!("Removing task '{}'", name);
+        }
+        if let Some(cron_handle) = self.handles.lock().await.remove(name) {
+            cron_handle.handle.abort();
+        }
     }
 
     pub async fn start(&self) {
+        self.started.store(true, Ordering::SeqCst);
         let tasks = self.tasks.clone();
         let tasks = tasks.lock().await.clone();
 
-        for (name, cron_task) in tasks.iter() {
-            let name = name.clone();
-            let schedule = cron_task.schedule.clone();
-            let task = cron_task.clone();
-
-            tokio::spawn(async move {
-                let mut upcoming = schedule.upcoming(Utc);
-                while let Some(next_time) = upcoming.next() {
-                    let now = Utc::now();
-                    if next_time > now {
-                        let duration = (next_time - now)
-                            .to_std()
-                            .unwrap_or_else(|_| Duration::from_secs(0));
-                        time::sleep(duration).await;
-                    }
-                    debug!("now is {}, Next time is {}", now, next_time);
-                    info!("Executing task: {}", name);
-                    (task.task)().await; // 异步执行任务
+        for (name, cron_task) in tasks.into_iter() {
+            self.spawn(name, cron_task).await;
+        }
+    }
+
+    /// 已注册但没有在调度的任务名，管理器未启动时返回全部任务；
+    /// 调度表已经结束的任务属于正常完成，不算在内
+    pub async fn stopped_tasks(&self) -> Vec<String> {
+        let names: Vec<String> = self.tasks.lock().await.keys().cloned().collect();
+        if !self.started.load(Ordering::SeqCst) {
+            return names;
+        }
+        let handles = self.handles.lock().await;
+        names
+            .into_iter()
+            .filter(|name| match handles.get(name) {
+                Some(cron_handle) => {
+                    cron_handle.handle.is_finished()
+                        && !cron_handle.completed.load(Ordering::SeqCst)
                 }
-            });
+                None => true,
+            })
+            .collect()
+    }
+
+    async fn spawn(&self, name: String, cron_task: CronTask) {
+        let task_name = name.clone();
+        let completed = Arc::new(AtomicBool::new(false));
+        let task_completed = completed.clone();
+        let handle = tokio::spawn(async move {
+            for next_time in cron_task.schedule.upcoming(cron_task.timezone) {
+                let next_time = next_time.with_timezone(&Utc);
+                let now = Utc::now();
+                if next_time > now {
+                    let duration = (next_time - now)
+                        .to_std()
+                        .unwrap_or_else(|_| Duration::from_secs(0));
+                    time::sleep(duration).await;
+                }
+                debug!("now is {}, Next time is {}", now, next_time);
+                info!("Executing task: {}", task_name);
+                let started = Instant::now();
+                (cron_task.task)().await; // 异步执行任务
+                histogram!(CRON_TASK_DURATION_SECONDS, "task" => task_name.clone())
+                    .record(started.elapsed().as_secs_f64());
+            }
+            info!("Task '{}' has no upcoming schedule", task_name);
+            task_completed.store(true, Ordering::SeqCst);
+        });
+
+        let cron_handle = CronHandle { handle, completed };
+        if let Some(previous) = self.handles.lock().await.insert(name, cron_handle) {
+            previous.handle.abort();
         }
     }
 }
+
+static CRON_TASK_MANAGER: OnceCell<Arc<CronTaskManager>> = OnceCell::new();
+
+pub fn init_cron_task_manager(manager: CronTaskManager) -> Arc<CronTaskManager> {
+    CRON_TASK_MANAGER.get_or_init(|| Arc::new(manager)).clone()
+}
+
+pub fn instance() -> Option<Arc<CronTaskManager>> {
+    CRON_TASK_MANAGER.get().cloned()
+}
+
+#[cfg(test)]
+mod test {
+    use super::CronTaskManager;
+    use futures::FutureExt;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_stopped_tasks() {
+        let manager = CronTaskManager::new();
+        // 调度表只包含过去的时间，循环立即正常结束
+        manager
+            .register_task(
+                "finished".to_string(),
+                "0 0 0 1 1 * 2000",
+                Arc::new(|| async {}.boxed()),
+            )
+            .await;
+        manager
+            .register_task(
+                "panicked".to_string(),
+                "* * * * * *",
+                Arc::new(|| async { panic!("boom") }.boxed()),
+            )
+            .await;
+        assert_eq!(manager.stopped_tasks().await.len(), 2);
+
+        manager.start().await;
+        tokio::time::sleep(Duration::from_millis(1500)).await;
+        assert_eq!(manager.stopped_tasks().await, vec!["panicked".to_string()]);
+    }
+}
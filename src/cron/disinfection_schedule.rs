@@ -0,0 +1,188 @@
+use crate::cron::cron_task_manager::{self, CronTaskManager};
+use crate::params::requests::uv_lamp::{BatchTurnParams, TurnParams};
+use crate::repositories::uv_lamp_schedules::{Schedule, UVLampSchedule};
+use crate::services::uv_lamp::control_service::ControlService;
+use anyhow::anyhow;
+use chrono::{NaiveTime, Timelike};
+use chrono_tz::Tz;
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use rand::Rng;
+use std::str::FromStr;
+use std::sync::Arc;
+use tracing::{error, info, warn};
+
+/// 消毒计划在定时任务管理器中的任务名
+pub fn task_name(id: u64) -> String {
+    format!("Disinfection schedule #{}", id)
+}
+
+/// 计划实际使用的 Cron 表达式，未设置表达式时由每周日历生成
+pub fn cron_expression(
+    cron_expression: &str,
+    weekdays: &str,
+    time_of_day: &str,
+) -> Result<String, anyhow::Error> {
+    let expression = if !cron_expression.trim().is_empty() {
+        cron_expression.trim().to_string()
+    } else if !weekdays.is_empty() && !time_of_day.is_empty() {
+        let time = NaiveTime::parse_from_str(time_of_day, "%H:%M")
+            .map_err(|_| anyhow!("Invalid time_of_day '{}', expected HH:MM", time_of_day))?;
+        format!("0 {} {} * * {}", time.minute(), time.hour(), weekdays)
+    } else {
+        return Err(anyhow!(
+            "Either cron_expression or weekdays with time_of_day is required"
+        ));
+    };
+    cron::Schedule::from_str(&expression)
+        .map_err(|e| anyhow!("Invalid cron expression '{}': {}", expression, e))?;
+    Ok(expression)
+}
+
+/// 计划使用的时区，为空时使用服务默认时区
+pub fn timezone(timezone: &str, default: Tz) -> Result<Tz, anyhow::Error> {
+    if timezone.is_empty() {
+        return Ok(default);
+    }
+    timezone
+        .parse()
+        .map_err(|_| anyhow!("Invalid timezone '{}'", timezone))
+}
+
+/// 启动时加载所有启用的消毒计划
+pub async fn load_all(manager: &CronTaskManager) {
+    let schedules = match UVLampSchedule::find_enabled().await {
+        Ok(schedules) => schedules,
+        Err(e) => {
+            error!("Failed to load disinfection schedules: {}", e);
+            return;
+        }
+    };
+    for schedule in schedules {
+        if let Err(e) = register(manager, &schedule).await {
+            error!("Failed to register disinfection schedule {}: {}", schedule.id, e);
+        }
+    }
+}
+
+/// 计划新增、修改或删除后重新注册，无需重启即可生效
+pub async fn reload(id: u64) -> Result<(), anyhow::Error> {
+    let manager = match cron_task_manager::instance() {
+        Some(manager) => manager,
+        None => {
+            warn!("Cron task manager not initialized, schedule {} will load on start", id);
+            return Ok(());
+        }
+    };
+    manager.remove_task(&task_name(id)).await;
+    match UVLampSchedule::find_by_id(id).await? {
+        Some(schedule) if schedule.is_enabled == 1 => register(&manager, &schedule).await,
+        _ => Ok(()),
+    }
+}
+
+async fn register(manager: &CronTaskManager, schedule: &Schedule) -> Result<(), anyhow::Error> {
+    let expression = cron_expression(
+        &schedule.cron_expression,
+        &schedule.weekdays,
+        &schedule.time_of_day,
+    )?;
+    let timezone = timezone(&schedule.timezone, manager.timezone())?;
+    let id = schedule.id;
+    manager
+        .register_task_with_timezone(
+            task_name(id),
+            &expression,
+            timezone,
+            Arc::new(move || execute(id)),
+        )
+        .await
+}
+
+fn execute(id: u64) -> BoxFuture<'static, ()> {
+    async move {
+        // 每次执行前重新读取，避免使用已被修改或删除的计划
+        let schedule = match UVLampSchedule::find_by_id(id).await {
+            Ok(Some(schedule)) if schedule.is_enabled == 1 => schedule,
+            Ok(_) => return,
+            Err(e) => {
+                error!("Failed to load disinfection schedule {}: {}", id, e);
+                return;
+            }
+        };
+
+        if schedule.group_id == 0 {
+            turn_device(&schedule).await;
+        } else {
+            turn_group(&schedule).await;
+        }
+
+        if let Err(e) = UVLampSchedule::update_last_run_at(id).await {
+            error!("Failed to update last run time of schedule {}: {}", id, e);
+        }
+    }
+    .boxed()
+}
+
+async fn turn_device(schedule: &Schedule) {
+    let params = TurnParams {
+        message_id: rand::thread_rng().gen_range(100_000..1_000_000),
+        device_number: Some(schedule.device_number.clone()),
+        group_id: None,
+        status: schedule.status == 1,
+        duration: schedule.duration,
+    };
+    match ControlService::turn(params).await {
+        Ok(message_id) => info!(
+            "Disinfection schedule {} executed, device {}, message {}",
+            schedule.id, schedule.device_number, message_id
+        ),
+        Err(e) => error!("Disinfection schedule {} failed: {}", schedule.id, e),
+    }
+}
+
+/// 分组计划展开为分组中的设备，由批量开关对每台设备下发与 `turn` 相同的指令
+async fn turn_group(schedule: &Schedule) {
+    let params = BatchTurnParams {
+        device_numbers: Vec::new(),
+        group_id: Some(schedule.group_id),
+        status: schedule.status == 1,
+        duration: schedule.duration,
+    };
+    match ControlService::batch_turn(params).await {
+        Ok(results) => {
+            let failed = results.iter().filter(|result| !result.success).count();
+            info!(
+                "Disinfection schedule {} executed, devices: {}, failed: {}",
+                schedule.id,
+                results.len(),
+                failed
+            );
+        }
+        Err(e) => error!("Disinfection schedule {} failed: {}", schedule.id, e),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::cron_expression;
+
+    #[test]
+    fn test_cron_expression_from_weekly_calendar() {
+        let expression = cron_expression("", "MON,WED,FRI", "07:30").unwrap();
+        assert_eq!(expression, "0 30 7 * * MON,WED,FRI");
+    }
+
+    #[test]
+    fn test_cron_expression_prefers_expression() {
+        let expression = cron_expression(" 0 0 22 * * * ", "MON", "07:30").unwrap();
+        assert_eq!(expression, "0 0 22 * * *");
+    }
+
+    #[test]
+    fn test_cron_expression_invalid() {
+        assert!(cron_expression("", "", "").is_err());
+        assert!(cron_expression("", "MON", "25:00").is_err());
+        assert!(cron_expression("not a cron", "", "").is_err());
+    }
+}
@@ -0,0 +1,16 @@
+use crate::repositories::idempotency_keys::IdempotencyKeys;
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use tracing::{error, info};
+
+/// 清理过期的幂等记录
+pub fn handle() -> BoxFuture<'static, ()> {
+    async move {
+        match IdempotencyKeys::delete_expired().await {
+            Ok(0) => {}
+            Ok(count) => info!("Deleted {} expired idempotency keys", count),
+            Err(e) => error!("Failed to delete expired idempotency keys: {}", e),
+        }
+    }
+    .boxed()
+}
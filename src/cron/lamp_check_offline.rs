@@ -3,6 +3,7 @@ use futures::FutureExt;
 use tracing::{error, info};
 use crate::repositories::uv_lamp_mqtt_notify_job::UVLampMqttNotifyJob;
 use crate::tasks::TaskType;
+use crate::utils::events::{get_event_bus, LampEvent};
 use crate::utils::mqtt::get_device_manager;
 
 pub fn handle() -> BoxFuture<'static, ()> {
@@ -13,14 +14,16 @@ pub fn handle() -> BoxFuture<'static, ()> {
         let offline_devices = manager.find_all_offline_devices();
         for device_number in offline_devices {
             info!("The device {} is offline!", device_number);
-            manager.update_status(&device_number, false);
+            if manager.update_status(&device_number, false) {
+                get_event_bus().publish(LampEvent::offline(device_number.clone()));
+            }
             create_job(device_number).await;
         }
     }.boxed()
 }
 
 async fn create_job(device_number: String) {
-    let result = UVLampMqttNotifyJob::create(device_number, "".to_string(), TaskType::LightStatusTask.to_string()).await;
+    let result = UVLampMqttNotifyJob::create(device_number, "".to_string(), TaskType::LightStatusTask.to_string(), None).await;
     match result {
         Ok(id) => info!("Created light switch notification job, id {}", id),
         Err(e) => error!("An error occurred: {}", e),
@@ -1,128 +1,5 @@
 pub mod check_lamp_status;
 pub mod cron_task_manager;
+pub mod disinfection_schedule;
+pub mod idempotency_key_cleanup;
 pub mod lamp_check_offline;
-
-const DEVICE_NUMBERS: [&str; 122] = [
-    "012005202000093",
-    "012005202000081",
-    "012101303000024",
-    "012101303000023",
-    "012101303000022",
-    "012101303000021",
-    "012101303000020",
-    "012101303000019",
-    "012101303000018",
-    "012101303000017",
-    "012101303000016",
-    "012101303000015",
-    "012101303000013",
-    "012101303000012",
-    "012101303000011",
-    "012101303000010",
-    "012101303000009",
-    "012101303000005",
-    "012101303000224",
-    "012101303000223",
-    "012101303000222",
-    "012101303000221",
-    "012101303000220",
-    "012101303000219",
-    "012101303000218",
-    "012101303000217",
-    "012101303000216",
-    "012101303000215",
-    "012101303000214",
-    "012101303000213",
-    "012101303000212",
-    "012101303000211",
-    "012101303000209",
-    "012101303000208",
-    "012101303000207",
-    "012101303000205",
-    "012101303000064",
-    "012101303000063",
-    "012101303000062",
-    "012101303000061",
-    "012101303000060",
-    "012101303000059",
-    "012101303000056",
-    "012101303000055",
-    "012101303000054",
-    "012101303000053",
-    "012101303000052",
-    "012101303000051",
-    "012101303000050",
-    "012101303000049",
-    "012101303000048",
-    "012101303000046",
-    "012101303000045",
-    "012101303000243",
-    "012101303000240",
-    "012101303000239",
-    "012101303000238",
-    "012101303000237",
-    "012101303000235",
-    "012101303000234",
-    "012101303000233",
-    "012101303000232",
-    "012101303000230",
-    "012101303000229",
-    "012101303000228",
-    "012101303000227",
-    "012101303000225",
-    "012101303000404",
-    "012101303000402",
-    "012101303000401",
-    "012101303000399",
-    "012101303000398",
-    "012101303000397",
-    "012101303000395",
-    "012101303000394",
-    "012101303000392",
-    "012101303000393",
-    "012101303000391",
-    "012101303000386",
-    "012101303000389",
-    "012101303000388",
-    "012101303000308",
-    "012101303000307",
-    "012101303000306",
-    "012101303000312",
-    "012101303000311",
-    "012101303000310",
-    "012101303000313",
-    "012101303000314",
-    "012101303000315",
-    "012101303000309",
-    "012101303000321",
-    "012101303000320",
-    "012101303000319",
-    "012101303000318",
-    "012101303000323",
-    "012101303000324",
-    "012101303000383",
-    "012101303000381",
-    "012005202000435",
-    "012101303000374",
-    "012101303000367",
-    "012101303000369",
-    "012101303000370",
-    "012101303000384",
-    "012101303000325",
-    "012101303000326",
-    "012101303000327",
-    "012101303000331",
-    "012101303000333",
-    "012101303000329",
-    "012101303000330",
-    "012101303000489",
-    "012101303000334",
-    "012101303000332",
-    "012101303000335",
-    "012101303000336",
-    "012101303000496",
-    "012101303000498",
-    "012101303000340",
-    "012101303000339",
-    "012101303000235",
-];
@@ -0,0 +1,103 @@
+use crate::params::requests::auth::IssueApiKeyParams;
+use crate::params::responses::auth::{ApiKeyResponse, IssuedApiKeyResponse};
+use crate::params::responses::common::ApiResponse;
+use crate::services::auth::api_key_service::ApiKeyService;
+use crate::services::auth::auth_service::CurrentUser;
+use crate::utils::error::{AppError, ErrorResponse};
+use axum::extract::Path;
+use axum::{Extension, Json};
+use chrono_tz::Tz;
+use std::sync::Arc;
+use tracing::info;
+use validator::Validate;
+
+#[utoipa::path(
+    post,
+    path = "/auth/api_keys",
+    tag = "auth",
+    summary = "签发 API Key，明文只在本次返回",
+    description = "需要 ADMIN 及以上角色",
+    request_body = IssueApiKeyParams,
+    responses(
+        (status = 200, description = "成功", body = ApiResponse<IssuedApiKeyResponse>),
+        (status = 400, description = "参数错误", body = ErrorResponse),
+        (status = 401, description = "未登录或凭证无效", body = ErrorResponse),
+        (status = 403, description = "角色权限不足", body = ErrorResponse),
+    ),
+    security(("bearer" = []), ("api_key" = [])),
+)]
+pub async fn issue_api_key(
+    Extension(timezone): Extension<Arc<Tz>>,
+    Extension(user): Extension<CurrentUser>,
+    Json(params): Json<IssueApiKeyParams>,
+) -> Result<ApiResponse<IssuedApiKeyResponse>, AppError> {
+    if let Err(e) = params.validate() {
+        return Err(AppError::from(e));
+    }
+    info!("Issue API key: {:?}, by {}", params, user.username);
+
+    match ApiKeyService::issue(params.name, params.role, params.expires_in_days, user.id).await {
+        Ok((api_key, key)) => Ok(ApiResponse::new(IssuedApiKeyResponse {
+            api_key: ApiKeyResponse::from_api_key(api_key, &timezone),
+            key,
+        })),
+        Err(e) => Err(AppError::from(e)),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/auth/api_keys",
+    tag = "auth",
+    summary = "API Key 列表",
+    description = "需要 ADMIN 及以上角色",
+    responses(
+        (status = 200, description = "成功", body = ApiResponse<Vec<ApiKeyResponse>>),
+        (status = 401, description = "未登录或凭证无效", body = ErrorResponse),
+        (status = 403, description = "角色权限不足", body = ErrorResponse),
+    ),
+    security(("bearer" = []), ("api_key" = [])),
+)]
+pub async fn list_api_keys(
+    Extension(timezone): Extension<Arc<Tz>>,
+) -> Result<ApiResponse<Vec<ApiKeyResponse>>, AppError> {
+    match ApiKeyService::list().await {
+        Ok(keys) => Ok(ApiResponse::new(
+            keys.into_iter()
+                .map(|key| ApiKeyResponse::from_api_key(key, &timezone))
+                .collect(),
+        )),
+        Err(e) => Err(AppError::from(e)),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/auth/api_keys/{id}",
+    tag = "auth",
+    summary = "吊销 API Key",
+    description = "需要 ADMIN 及以上角色",
+    params(
+        ("id" = u64, Path, description = "API Key ID"),
+    ),
+    responses(
+        (status = 200, description = "成功", body = ApiResponse<ApiKeyResponse>),
+        (status = 401, description = "未登录或凭证无效", body = ErrorResponse),
+        (status = 403, description = "角色权限不足", body = ErrorResponse),
+        (status = 404, description = "资源不存在", body = ErrorResponse),
+        (status = 409, description = "API Key 已吊销", body = ErrorResponse),
+    ),
+    security(("bearer" = []), ("api_key" = [])),
+)]
+pub async fn revoke_api_key(
+    Extension(timezone): Extension<Arc<Tz>>,
+    Extension(user): Extension<CurrentUser>,
+    Path(id): Path<u64>,
+) -> Result<ApiResponse<ApiKeyResponse>, AppError> {
+    info!("Revoke API key {}, by {}", id, user.username);
+
+    match ApiKeyService::revoke(id).await {
+        Ok(key) => Ok(ApiResponse::new(ApiKeyResponse::from_api_key(key, &timezone))),
+        Err(e) => Err(AppError::from(e)),
+    }
+}
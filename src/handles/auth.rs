@@ -0,0 +1,129 @@
+use crate::params::requests::auth::{CreateUserParams, LoginParams, UpdateUserParams};
+use crate::params::responses::auth::{LoginResponse, UserResponse};
+use crate::params::responses::common::ApiResponse;
+use crate::services::auth::auth_service::{AuthService, CurrentUser};
+use crate::utils::error::{AppError, ErrorResponse};
+use axum::extract::Path;
+use axum::{Extension, Json};
+use chrono_tz::Tz;
+use std::sync::Arc;
+use tracing::{info, warn};
+use validator::Validate;
+
+#[utoipa::path(
+    post,
+    path = "/auth/login",
+    tag = "auth",
+    summary = "登录并获取访问令牌",
+    request_body = LoginParams,
+    responses(
+        (status = 200, description = "成功", body = ApiResponse<LoginResponse>),
+        (status = 400, description = "参数错误", body = ErrorResponse),
+        (status = 401, description = "用户名或密码错误", body = ErrorResponse),
+    ),
+)]
+pub async fn login(Json(params): Json<LoginParams>) -> Result<ApiResponse<LoginResponse>, AppError> {
+    if let Err(e) = params.validate() {
+        return Err(AppError::from(e));
+    }
+    info!("Login: {}", params.username);
+
+    match AuthService::login(&params.username, &params.password).await {
+        Ok(token) => Ok(ApiResponse::new(LoginResponse::bearer(token))),
+        Err(e) => {
+            warn!("Login failed for {}: {}", params.username, e);
+            Err(AppError::from(e))
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/auth/users",
+    tag = "auth",
+    summary = "新建用户",
+    description = "需要 ADMIN 及以上角色",
+    request_body = CreateUserParams,
+    responses(
+        (status = 200, description = "成功", body = ApiResponse<UserResponse>),
+        (status = 400, description = "参数错误", body = ErrorResponse),
+        (status = 401, description = "未登录或凭证无效", body = ErrorResponse),
+        (status = 403, description = "角色权限不足", body = ErrorResponse),
+    ),
+    security(("bearer" = []), ("api_key" = [])),
+)]
+pub async fn create_user(
+    Extension(timezone): Extension<Arc<Tz>>,
+    Extension(user): Extension<CurrentUser>,
+    Json(params): Json<CreateUserParams>,
+) -> Result<ApiResponse<UserResponse>, AppError> {
+    if let Err(e) = params.validate() {
+        return Err(AppError::from(e));
+    }
+    info!("Create user: {} ({}), by {}", params.username, params.role, user.username);
+
+    match AuthService::create_user(params.username, &params.password, params.role).await {
+        Ok(created) => Ok(ApiResponse::new(UserResponse::from_user(created, &timezone))),
+        Err(e) => Err(AppError::from(e)),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/auth/users",
+    tag = "auth",
+    summary = "用户列表",
+    description = "需要 ADMIN 及以上角色",
+    responses(
+        (status = 200, description = "成功", body = ApiResponse<Vec<UserResponse>>),
+        (status = 401, description = "未登录或凭证无效", body = ErrorResponse),
+        (status = 403, description = "角色权限不足", body = ErrorResponse),
+    ),
+    security(("bearer" = []), ("api_key" = [])),
+)]
+pub async fn list_users(
+    Extension(timezone): Extension<Arc<Tz>>,
+) -> Result<ApiResponse<Vec<UserResponse>>, AppError> {
+    match AuthService::list_users().await {
+        Ok(users) => Ok(ApiResponse::new(
+            users
+                .into_iter()
+                .map(|user| UserResponse::from_user(user, &timezone))
+                .collect(),
+        )),
+        Err(e) => Err(AppError::from(e)),
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/auth/users/{id}",
+    tag = "auth",
+    summary = "修改用户角色或禁用用户",
+    description = "需要 ADMIN 及以上角色",
+    params(
+        ("id" = u64, Path, description = "用户 ID"),
+    ),
+    request_body = UpdateUserParams,
+    responses(
+        (status = 200, description = "成功", body = ApiResponse<UserResponse>),
+        (status = 400, description = "参数错误", body = ErrorResponse),
+        (status = 401, description = "未登录或凭证无效", body = ErrorResponse),
+        (status = 403, description = "角色权限不足", body = ErrorResponse),
+        (status = 404, description = "资源不存在", body = ErrorResponse),
+    ),
+    security(("bearer" = []), ("api_key" = [])),
+)]
+pub async fn update_user(
+    Extension(timezone): Extension<Arc<Tz>>,
+    Extension(user): Extension<CurrentUser>,
+    Path(id): Path<u64>,
+    Json(params): Json<UpdateUserParams>,
+) -> Result<ApiResponse<UserResponse>, AppError> {
+    info!("Update user {}: {:?}, by {}", id, params, user.username);
+
+    match AuthService::update_user(id, params.role, params.is_disabled).await {
+        Ok(updated) => Ok(ApiResponse::new(UserResponse::from_user(updated, &timezone))),
+        Err(e) => Err(AppError::from(e)),
+    }
+}
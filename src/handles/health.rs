@@ -0,0 +1,41 @@
+use crate::params::responses::health::{LivenessResponse, ReadinessResponse};
+use crate::services::health::health_service::HealthService;
+use axum::http::StatusCode;
+use axum::Json;
+
+#[utoipa::path(
+    get,
+    path = "/healthz",
+    tag = "health",
+    summary = "存活检查",
+    description = "进程能响应请求即返回成功，不检查外部依赖",
+    responses(
+        (status = 200, description = "成功", body = LivenessResponse),
+    ),
+)]
+pub async fn healthz() -> Json<LivenessResponse> {
+    Json(LivenessResponse {
+        status: "ok".to_string(),
+    })
+}
+
+#[utoipa::path(
+    get,
+    path = "/readyz",
+    tag = "health",
+    summary = "就绪检查",
+    description = "检查 MySQL、MQTT 连接、后台任务和定时任务，任一不可用时返回 503 并给出原因",
+    responses(
+        (status = 200, description = "成功", body = ReadinessResponse),
+        (status = 503, description = "存在不可用的依赖", body = ReadinessResponse),
+    ),
+)]
+pub async fn readyz() -> (StatusCode, Json<ReadinessResponse>) {
+    let response = ReadinessResponse::from_readiness(HealthService::check().await);
+    let status = if response.ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, Json(response))
+}
@@ -0,0 +1,11 @@
+use crate::utils::metrics::render;
+use axum::http::header;
+use axum::response::IntoResponse;
+
+/// Prometheus 抓取接口，返回文本格式的指标
+pub async fn metrics() -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        render().await,
+    )
+}
@@ -1 +1,9 @@
+pub mod api_key;
+pub mod auth;
+pub mod health;
+pub mod metrics;
 pub mod uv_lamp;
+pub mod uv_lamp_device;
+pub mod uv_lamp_group;
+pub mod uv_lamp_notify_job;
+pub mod uv_lamp_schedule;
@@ -1,19 +1,244 @@
-use crate::params::requests::uv_lamp::TurnParams;
-use crate::params::responses::common::{ApiResponse, Empty};
+use crate::params::requests::uv_lamp::{
+    BatchTurnParams, ListCommandsParams, ListMessagesParams, StreamEventsParams, TurnParams,
+    TurnQuery,
+};
+use crate::params::responses::common::{ApiResponse, Empty, PageResponse};
+use crate::params::responses::uv_lamp::{
+    BatchTurnResponse, CommandResponse, MqttStatusResponse, ReceivedMessageResponse,
+    TurnReplyResponse,
+};
 use crate::services::uv_lamp::control_service::ControlService;
-use crate::utils::error::AppError;
-use axum::Json;
+use crate::services::uv_lamp::event_service::EventService;
+use crate::services::uv_lamp::message_service::MessageService;
+use crate::services::uv_lamp::status_service::StatusService;
+use crate::utils::error::{AppError, ErrorResponse};
+use crate::utils::events::LampEvent;
+use axum::extract::Query;
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
+use axum::{Extension, Json};
+use chrono_tz::Tz;
+use futures::{Stream, StreamExt};
+use std::convert::Infallible;
+use std::sync::Arc;
 use tracing::info;
 use validator::Validate;
 
-pub async fn turn(Json(params): Json<TurnParams>) -> Result<ApiResponse<Empty>, AppError> {
+#[utoipa::path(
+    post,
+    path = "/uv_lamp/turn",
+    tag = "uv_lamp",
+    summary = "开关紫外线灯，传 `wait` 时等待设备回复并返回回复内容，否则 `data` 为空对象",
+    description = "需要 OPERATOR 及以上角色。传 `group_id` 时向分组中的设备下发指令，`data` 为 `BatchTurnResponse`，此时不支持 `wait`",
+    params(
+        TurnQuery,
+        ("Idempotency-Key" = Option<String>, Header, description = "幂等键，相同键的重复请求返回首次请求的结果，不再下发指令；不传时按设备编号(或分组 ID)和 `message_id` 在时间窗口内去重，参数不同时视为新的指令"),
+    ),
+    request_body = TurnParams,
+    responses(
+        (status = 200, description = "成功", body = ApiResponse<TurnReplyResponse>),
+        (status = 400, description = "参数错误", body = ErrorResponse),
+        (status = 401, description = "未登录或凭证无效", body = ErrorResponse),
+        (status = 403, description = "角色权限不足", body = ErrorResponse),
+        (status = 404, description = "分组不存在", body = ErrorResponse),
+        (status = 409, description = "设备离线、与未确认的指令冲突或幂等键冲突", body = ErrorResponse),
+        (status = 429, description = "设备指令过于频繁", body = ErrorResponse),
+        (status = 503, description = "MQTT 不可用", body = ErrorResponse),
+        (status = 504, description = "等待设备回复超时，指令已经发出；去重窗口内的重试返回同一结果，之后才会重新下发", body = ErrorResponse),
+    ),
+    security(("bearer" = []), ("api_key" = [])),
+)]
+pub async fn turn(
+    Query(query): Query<TurnQuery>,
+    Json(params): Json<TurnParams>,
+) -> Result<Response, AppError> {
     if let Err(e) = params.validate() {
-        return Err(AppError::new(format!("Invalid ID parameters: {:?}", e)));
+        return Err(AppError::from(e));
     }
-    info!("Turn Light: {:?}", params);
+    let wait = match query.wait_duration() {
+        Ok(wait) => wait,
+        Err(e) => return Err(AppError::BadRequest(format!("Invalid wait parameter: {}", e))),
+    };
+    info!("Turn Light: {:?}, wait: {:?}", params, wait);
 
-    match ControlService::turn(params).await {
-        Ok(_) => Ok(ApiResponse::new(Empty {})),
-        Err(e) => Err(AppError::new(e.to_string())),
+    if params.group_id.is_some() {
+        if wait.is_some() {
+            return Err(AppError::BadRequest(
+                "wait is not supported when turning a group".to_string(),
+            ));
+        }
+        return match ControlService::turn_group(params).await {
+            Ok(results) => Ok(ApiResponse::new(BatchTurnResponse::from(results)).into_response()),
+            Err(e) => Err(AppError::from(e)),
+        };
+    }
+
+    let Some(wait) = wait else {
+        return match ControlService::turn(params).await {
+            Ok(_) => Ok(ApiResponse::new(Empty {}).into_response()),
+            Err(e) => Err(AppError::from(e)),
+        };
+    };
+
+    match ControlService::turn_and_wait(params, wait).await {
+        Ok(reply) => Ok(ApiResponse::new(TurnReplyResponse::from(reply)).into_response()),
+        Err(e) => Err(AppError::from(e)),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/uv_lamp/batch_turn",
+    tag = "uv_lamp",
+    summary = "批量开关多台设备或整个分组",
+    description = "需要 OPERATOR 及以上角色。合并分组中的设备并去重后最多 500 台",
+    params(
+        ("Idempotency-Key" = Option<String>, Header, description = "幂等键，相同键的重复请求返回首次请求的结果，不再下发指令"),
+    ),
+    request_body = BatchTurnParams,
+    responses(
+        (status = 200, description = "成功", body = ApiResponse<BatchTurnResponse>),
+        (status = 400, description = "参数错误", body = ErrorResponse),
+        (status = 401, description = "未登录或凭证无效", body = ErrorResponse),
+        (status = 403, description = "角色权限不足", body = ErrorResponse),
+        (status = 503, description = "MQTT 不可用", body = ErrorResponse),
+    ),
+    security(("bearer" = []), ("api_key" = [])),
+)]
+pub async fn batch_turn(
+    Json(params): Json<BatchTurnParams>,
+) -> Result<ApiResponse<BatchTurnResponse>, AppError> {
+    if let Err(e) = params.validate() {
+        return Err(AppError::from(e));
+    }
+    info!("Batch turn light: {:?}", params);
+
+    match ControlService::batch_turn(params).await {
+        Ok(results) => Ok(ApiResponse::new(BatchTurnResponse::from(results))),
+        Err(e) => Err(AppError::from(e)),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/uv_lamp/commands",
+    tag = "uv_lamp",
+    summary = "下发指令及确认状态",
+    description = "需要 VIEWER 及以上角色",
+    params(
+        ListCommandsParams,
+    ),
+    responses(
+        (status = 200, description = "成功", body = ApiResponse<PageResponse<CommandResponse>>),
+        (status = 400, description = "参数错误", body = ErrorResponse),
+        (status = 401, description = "未登录或凭证无效", body = ErrorResponse),
+        (status = 403, description = "角色权限不足", body = ErrorResponse),
+    ),
+    security(("bearer" = []), ("api_key" = [])),
+)]
+pub async fn list_commands(
+    Extension(timezone): Extension<Arc<Tz>>,
+    Query(params): Query<ListCommandsParams>,
+) -> Result<ApiResponse<PageResponse<CommandResponse>>, AppError> {
+    match ControlService::list_commands(params).await {
+        Ok((messages, total, pagination)) => {
+            let items = messages
+                .into_iter()
+                .map(|message| CommandResponse::from_message(message, &timezone))
+                .collect();
+            Ok(ApiResponse::new(PageResponse::new(items, total, pagination)))
+        }
+        Err(e) => Err(AppError::from(e)),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/uv_lamp/messages",
+    tag = "uv_lamp",
+    summary = "设备上报的 MQTT 消息",
+    description = "需要 VIEWER 及以上角色",
+    params(
+        ListMessagesParams,
+    ),
+    responses(
+        (status = 200, description = "成功", body = ApiResponse<PageResponse<ReceivedMessageResponse>>),
+        (status = 400, description = "参数错误", body = ErrorResponse),
+        (status = 401, description = "未登录或凭证无效", body = ErrorResponse),
+        (status = 403, description = "角色权限不足", body = ErrorResponse),
+    ),
+    security(("bearer" = []), ("api_key" = [])),
+)]
+pub async fn list_messages(
+    Extension(timezone): Extension<Arc<Tz>>,
+    Query(params): Query<ListMessagesParams>,
+) -> Result<ApiResponse<PageResponse<ReceivedMessageResponse>>, AppError> {
+    match MessageService::list(params, &timezone).await {
+        Ok((messages, total, pagination)) => {
+            let items = messages
+                .into_iter()
+                .map(|message| ReceivedMessageResponse::from_message(message, &timezone))
+                .collect();
+            Ok(ApiResponse::new(PageResponse::new(items, total, pagination)))
+        }
+        Err(e) => Err(AppError::from(e)),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/uv_lamp/events",
+    tag = "uv_lamp",
+    summary = "以 Server-Sent Events 推送设备状态变化、上下线和红外报警",
+    description = "需要 VIEWER 及以上角色，事件名为 `status_changed`、`online`、`offline`、`infrared_alarm`，数据为 `LampEvent`",
+    params(StreamEventsParams),
+    responses(
+        (status = 200, description = "事件流", body = LampEvent, content_type = "text/event-stream"),
+        (status = 401, description = "未登录或凭证无效", body = ErrorResponse),
+        (status = 403, description = "角色权限不足", body = ErrorResponse),
+        (status = 404, description = "分组不存在", body = ErrorResponse),
+    ),
+    security(("bearer" = []), ("api_key" = [])),
+)]
+pub async fn stream_events(
+    Query(params): Query<StreamEventsParams>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, AppError> {
+    info!("Subscribe events: {:?}", params);
+
+    match EventService::subscribe(params).await {
+        Ok(events) => {
+            let stream = events.map(|event| {
+                let sse_event = Event::default()
+                    .event(event.name())
+                    .json_data(&event)
+                    .unwrap_or_else(|_| Event::default().event(event.name()));
+                Ok(sse_event)
+            });
+            Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+        }
+        Err(e) => Err(AppError::from(e)),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/uv_lamp/mqtt/status",
+    tag = "uv_lamp",
+    summary = "MQTT 连接状态",
+    description = "需要 VIEWER 及以上角色",
+    responses(
+        (status = 200, description = "成功", body = ApiResponse<MqttStatusResponse>),
+        (status = 401, description = "未登录或凭证无效", body = ErrorResponse),
+        (status = 403, description = "角色权限不足", body = ErrorResponse),
+        (status = 503, description = "MQTT 未初始化", body = ErrorResponse),
+    ),
+    security(("bearer" = []), ("api_key" = [])),
+)]
+pub async fn mqtt_status(
+    Extension(timezone): Extension<Arc<Tz>>,
+) -> Result<ApiResponse<MqttStatusResponse>, AppError> {
+    match StatusService::mqtt() {
+        Ok(status) => Ok(ApiResponse::new(MqttStatusResponse::from_status(status, &timezone))),
+        Err(e) => Err(AppError::from(e)),
     }
 }
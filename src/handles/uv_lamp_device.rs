@@ -0,0 +1,229 @@
+use crate::params::requests::uv_lamp_device::{
+    CreateDeviceParams, ListDeviceStatusParams, ListDevicesParams, UpdateDeviceParams,
+};
+use crate::params::responses::common::ApiResponse;
+use crate::params::responses::uv_lamp_device::{DeviceResponse, DeviceStatusResponse};
+use crate::services::uv_lamp::device_service::DeviceService;
+use crate::services::uv_lamp::status_service::StatusService;
+use crate::utils::error::{AppError, ErrorResponse};
+use axum::extract::{Path, Query};
+use axum::{Extension, Json};
+use chrono_tz::Tz;
+use std::sync::Arc;
+use tracing::info;
+use validator::Validate;
+
+#[utoipa::path(
+    post,
+    path = "/uv_lamp/devices",
+    tag = "device",
+    summary = "登记设备",
+    description = "需要 ADMIN 及以上角色；已停用的设备重新登记时恢复启用",
+    request_body = CreateDeviceParams,
+    responses(
+        (status = 200, description = "成功", body = ApiResponse<DeviceResponse>),
+        (status = 400, description = "参数错误", body = ErrorResponse),
+        (status = 401, description = "未登录或凭证无效", body = ErrorResponse),
+        (status = 403, description = "角色权限不足", body = ErrorResponse),
+        (status = 409, description = "设备已登记且在用", body = ErrorResponse),
+    ),
+    security(("bearer" = []), ("api_key" = [])),
+)]
+pub async fn create_device(
+    Extension(timezone): Extension<Arc<Tz>>,
+    Json(params): Json<CreateDeviceParams>,
+) -> Result<ApiResponse<DeviceResponse>, AppError> {
+    if let Err(e) = params.validate() {
+        return Err(AppError::from(e));
+    }
+    info!("Create device: {:?}", params);
+
+    match DeviceService::create(params).await {
+        Ok(device) => Ok(ApiResponse::new(DeviceResponse::from_device(device, &timezone))),
+        Err(e) => Err(AppError::from(e)),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/uv_lamp/devices",
+    tag = "device",
+    summary = "设备列表",
+    description = "需要 VIEWER 及以上角色",
+    params(
+        ListDevicesParams,
+    ),
+    responses(
+        (status = 200, description = "成功", body = ApiResponse<Vec<DeviceResponse>>),
+        (status = 400, description = "参数错误", body = ErrorResponse),
+        (status = 401, description = "未登录或凭证无效", body = ErrorResponse),
+        (status = 403, description = "角色权限不足", body = ErrorResponse),
+    ),
+    security(("bearer" = []), ("api_key" = [])),
+)]
+pub async fn list_devices(
+    Extension(timezone): Extension<Arc<Tz>>,
+    Query(params): Query<ListDevicesParams>,
+) -> Result<ApiResponse<Vec<DeviceResponse>>, AppError> {
+    match DeviceService::list(params.include_decommissioned).await {
+        Ok(devices) => Ok(ApiResponse::new(
+            devices
+                .into_iter()
+                .map(|device| DeviceResponse::from_device(device, &timezone))
+                .collect(),
+        )),
+        Err(e) => Err(AppError::from(e)),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/uv_lamp/devices/{device_number}",
+    tag = "device",
+    summary = "设备详情",
+    description = "需要 VIEWER 及以上角色",
+    params(
+        ("device_number" = String, Path, description = "设备编号"),
+    ),
+    responses(
+        (status = 200, description = "成功", body = ApiResponse<DeviceResponse>),
+        (status = 401, description = "未登录或凭证无效", body = ErrorResponse),
+        (status = 403, description = "角色权限不足", body = ErrorResponse),
+        (status = 404, description = "资源不存在", body = ErrorResponse),
+    ),
+    security(("bearer" = []), ("api_key" = [])),
+)]
+pub async fn show_device(
+    Extension(timezone): Extension<Arc<Tz>>,
+    Path(device_number): Path<String>,
+) -> Result<ApiResponse<DeviceResponse>, AppError> {
+    match DeviceService::find(&device_number).await {
+        Ok(device) => Ok(ApiResponse::new(DeviceResponse::from_device(device, &timezone))),
+        Err(e) => Err(AppError::from(e)),
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/uv_lamp/devices/{device_number}",
+    tag = "device",
+    summary = "修改设备",
+    description = "需要 ADMIN 及以上角色",
+    params(
+        ("device_number" = String, Path, description = "设备编号"),
+    ),
+    request_body = UpdateDeviceParams,
+    responses(
+        (status = 200, description = "成功", body = ApiResponse<DeviceResponse>),
+        (status = 400, description = "参数错误", body = ErrorResponse),
+        (status = 401, description = "未登录或凭证无效", body = ErrorResponse),
+        (status = 403, description = "角色权限不足", body = ErrorResponse),
+        (status = 404, description = "资源不存在", body = ErrorResponse),
+    ),
+    security(("bearer" = []), ("api_key" = [])),
+)]
+pub async fn update_device(
+    Extension(timezone): Extension<Arc<Tz>>,
+    Path(device_number): Path<String>,
+    Json(params): Json<UpdateDeviceParams>,
+) -> Result<ApiResponse<DeviceResponse>, AppError> {
+    if let Err(e) = params.validate() {
+        return Err(AppError::from(e));
+    }
+    info!("Update device {}: {:?}", device_number, params);
+
+    match DeviceService::update(&device_number, params).await {
+        Ok(device) => Ok(ApiResponse::new(DeviceResponse::from_device(device, &timezone))),
+        Err(e) => Err(AppError::from(e)),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/uv_lamp/devices/{device_number}",
+    tag = "device",
+    summary = "停用设备",
+    description = "需要 ADMIN 及以上角色",
+    params(
+        ("device_number" = String, Path, description = "设备编号"),
+    ),
+    responses(
+        (status = 200, description = "成功", body = ApiResponse<DeviceResponse>),
+        (status = 401, description = "未登录或凭证无效", body = ErrorResponse),
+        (status = 403, description = "角色权限不足", body = ErrorResponse),
+        (status = 404, description = "资源不存在", body = ErrorResponse),
+    ),
+    security(("bearer" = []), ("api_key" = [])),
+)]
+pub async fn decommission_device(
+    Extension(timezone): Extension<Arc<Tz>>,
+    Path(device_number): Path<String>,
+) -> Result<ApiResponse<DeviceResponse>, AppError> {
+    info!("Decommission device {}", device_number);
+
+    match DeviceService::decommission(&device_number).await {
+        Ok(device) => Ok(ApiResponse::new(DeviceResponse::from_device(device, &timezone))),
+        Err(e) => Err(AppError::from(e)),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/uv_lamp/devices/{device_number}/status",
+    tag = "device",
+    summary = "设备在线状态",
+    description = "需要 VIEWER 及以上角色",
+    params(
+        ("device_number" = String, Path, description = "设备编号"),
+    ),
+    responses(
+        (status = 200, description = "成功", body = ApiResponse<DeviceStatusResponse>),
+        (status = 401, description = "未登录或凭证无效", body = ErrorResponse),
+        (status = 403, description = "角色权限不足", body = ErrorResponse),
+        (status = 404, description = "资源不存在", body = ErrorResponse),
+    ),
+    security(("bearer" = []), ("api_key" = [])),
+)]
+pub async fn device_status(
+    Extension(timezone): Extension<Arc<Tz>>,
+    Path(device_number): Path<String>,
+) -> Result<ApiResponse<DeviceStatusResponse>, AppError> {
+    match StatusService::find(&device_number).await {
+        Ok(status) => Ok(ApiResponse::new(DeviceStatusResponse::from_status(
+            status, &timezone,
+        ))),
+        Err(e) => Err(AppError::from(e)),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/uv_lamp/devices/status",
+    tag = "device",
+    summary = "设备在线状态列表",
+    description = "需要 VIEWER 及以上角色",
+    params(
+        ListDeviceStatusParams,
+    ),
+    responses(
+        (status = 200, description = "成功", body = ApiResponse<Vec<DeviceStatusResponse>>),
+        (status = 400, description = "参数错误", body = ErrorResponse),
+        (status = 401, description = "未登录或凭证无效", body = ErrorResponse),
+        (status = 403, description = "角色权限不足", body = ErrorResponse),
+    ),
+    security(("bearer" = []), ("api_key" = [])),
+)]
+pub async fn list_device_status(
+    Extension(timezone): Extension<Arc<Tz>>,
+    Query(params): Query<ListDeviceStatusParams>,
+) -> Result<ApiResponse<Vec<DeviceStatusResponse>>, AppError> {
+    match StatusService::list(params.online, params.group_id).await {
+        Ok(statuses) => Ok(ApiResponse::new(
+            statuses
+                .into_iter()
+                .map(|status| DeviceStatusResponse::from_status(status, &timezone))
+                .collect(),
+        )),
+        Err(e) => Err(AppError::from(e)),
+    }
+}
@@ -0,0 +1,229 @@
+use crate::params::requests::uv_lamp_group::{
+    CreateGroupParams, GroupDevicesParams, UpdateGroupParams,
+};
+use crate::params::responses::common::{ApiResponse, Empty};
+use crate::params::responses::uv_lamp_group::{GroupDetailResponse, GroupResponse};
+use crate::services::uv_lamp::group_service::GroupService;
+use crate::utils::error::{AppError, ErrorResponse};
+use axum::extract::Path;
+use axum::{Extension, Json};
+use chrono_tz::Tz;
+use std::sync::Arc;
+use tracing::info;
+use validator::Validate;
+
+#[utoipa::path(
+    post,
+    path = "/uv_lamp/groups",
+    tag = "group",
+    summary = "新建分组",
+    description = "需要 ADMIN 及以上角色",
+    request_body = CreateGroupParams,
+    responses(
+        (status = 200, description = "成功", body = ApiResponse<GroupResponse>),
+        (status = 400, description = "参数错误", body = ErrorResponse),
+        (status = 401, description = "未登录或凭证无效", body = ErrorResponse),
+        (status = 403, description = "角色权限不足", body = ErrorResponse),
+    ),
+    security(("bearer" = []), ("api_key" = [])),
+)]
+pub async fn create_group(
+    Extension(timezone): Extension<Arc<Tz>>,
+    Json(params): Json<CreateGroupParams>,
+) -> Result<ApiResponse<GroupResponse>, AppError> {
+    if let Err(e) = params.validate() {
+        return Err(AppError::from(e));
+    }
+    info!("Create device group: {:?}", params);
+
+    match GroupService::create(params).await {
+        Ok(group) => Ok(ApiResponse::new(GroupResponse::from_group(group, &timezone))),
+        Err(e) => Err(AppError::from(e)),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/uv_lamp/groups",
+    tag = "group",
+    summary = "分组列表",
+    description = "需要 VIEWER 及以上角色",
+    responses(
+        (status = 200, description = "成功", body = ApiResponse<Vec<GroupResponse>>),
+        (status = 401, description = "未登录或凭证无效", body = ErrorResponse),
+        (status = 403, description = "角色权限不足", body = ErrorResponse),
+    ),
+    security(("bearer" = []), ("api_key" = [])),
+)]
+pub async fn list_groups(
+    Extension(timezone): Extension<Arc<Tz>>,
+) -> Result<ApiResponse<Vec<GroupResponse>>, AppError> {
+    match GroupService::list().await {
+        Ok(groups) => Ok(ApiResponse::new(
+            groups
+                .into_iter()
+                .map(|group| GroupResponse::from_group(group, &timezone))
+                .collect(),
+        )),
+        Err(e) => Err(AppError::from(e)),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/uv_lamp/groups/{id}",
+    tag = "group",
+    summary = "分组详情及其中的设备",
+    description = "需要 VIEWER 及以上角色",
+    params(
+        ("id" = u64, Path, description = "分组 ID"),
+    ),
+    responses(
+        (status = 200, description = "成功", body = ApiResponse<GroupDetailResponse>),
+        (status = 401, description = "未登录或凭证无效", body = ErrorResponse),
+        (status = 403, description = "角色权限不足", body = ErrorResponse),
+        (status = 404, description = "资源不存在", body = ErrorResponse),
+    ),
+    security(("bearer" = []), ("api_key" = [])),
+)]
+pub async fn show_group(
+    Extension(timezone): Extension<Arc<Tz>>,
+    Path(id): Path<u64>,
+) -> Result<ApiResponse<GroupDetailResponse>, AppError> {
+    let group = match GroupService::find(id).await {
+        Ok(group) => group,
+        Err(e) => return Err(AppError::from(e)),
+    };
+    match GroupService::resolve_device_numbers(id).await {
+        Ok(device_numbers) => Ok(ApiResponse::new(GroupDetailResponse {
+            group: GroupResponse::from_group(group, &timezone),
+            device_numbers,
+        })),
+        Err(e) => Err(AppError::from(e)),
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/uv_lamp/groups/{id}",
+    tag = "group",
+    summary = "修改分组",
+    description = "需要 ADMIN 及以上角色",
+    params(
+        ("id" = u64, Path, description = "分组 ID"),
+    ),
+    request_body = UpdateGroupParams,
+    responses(
+        (status = 200, description = "成功", body = ApiResponse<GroupResponse>),
+        (status = 400, description = "参数错误", body = ErrorResponse),
+        (status = 401, description = "未登录或凭证无效", body = ErrorResponse),
+        (status = 403, description = "角色权限不足", body = ErrorResponse),
+        (status = 404, description = "资源不存在", body = ErrorResponse),
+    ),
+    security(("bearer" = []), ("api_key" = [])),
+)]
+pub async fn update_group(
+    Extension(timezone): Extension<Arc<Tz>>,
+    Path(id): Path<u64>,
+    Json(params): Json<UpdateGroupParams>,
+) -> Result<ApiResponse<GroupResponse>, AppError> {
+    if let Err(e) = params.validate() {
+        return Err(AppError::from(e));
+    }
+    info!("Update device group {}: {:?}", id, params);
+
+    match GroupService::update(id, params).await {
+        Ok(group) => Ok(ApiResponse::new(GroupResponse::from_group(group, &timezone))),
+        Err(e) => Err(AppError::from(e)),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/uv_lamp/groups/{id}",
+    tag = "group",
+    summary = "删除分组",
+    description = "需要 ADMIN 及以上角色",
+    params(
+        ("id" = u64, Path, description = "分组 ID"),
+    ),
+    responses(
+        (status = 200, description = "成功", body = ApiResponse<Empty>),
+        (status = 401, description = "未登录或凭证无效", body = ErrorResponse),
+        (status = 403, description = "角色权限不足", body = ErrorResponse),
+        (status = 404, description = "资源不存在", body = ErrorResponse),
+        (status = 409, description = "分组下还有子分组或被消毒计划使用", body = ErrorResponse),
+    ),
+    security(("bearer" = []), ("api_key" = [])),
+)]
+pub async fn delete_group(Path(id): Path<u64>) -> Result<ApiResponse<Empty>, AppError> {
+    info!("Delete device group {}", id);
+
+    match GroupService::delete(id).await {
+        Ok(_) => Ok(ApiResponse::new(Empty {})),
+        Err(e) => Err(AppError::from(e)),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/uv_lamp/groups/{id}/devices",
+    tag = "group",
+    summary = "向分组添加设备",
+    description = "需要 ADMIN 及以上角色",
+    params(
+        ("id" = u64, Path, description = "分组 ID"),
+    ),
+    request_body = GroupDevicesParams,
+    responses(
+        (status = 200, description = "成功", body = ApiResponse<Empty>),
+        (status = 400, description = "参数错误", body = ErrorResponse),
+        (status = 401, description = "未登录或凭证无效", body = ErrorResponse),
+        (status = 403, description = "角色权限不足", body = ErrorResponse),
+        (status = 404, description = "资源不存在", body = ErrorResponse),
+    ),
+    security(("bearer" = []), ("api_key" = [])),
+)]
+pub async fn add_group_devices(
+    Path(id): Path<u64>,
+    Json(params): Json<GroupDevicesParams>,
+) -> Result<ApiResponse<Empty>, AppError> {
+    if let Err(e) = params.validate() {
+        return Err(AppError::from(e));
+    }
+    info!("Add devices to group {}: {:?}", id, params);
+
+    match GroupService::add_devices(id, params.device_numbers).await {
+        Ok(_) => Ok(ApiResponse::new(Empty {})),
+        Err(e) => Err(AppError::from(e)),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/uv_lamp/groups/{id}/devices/{device_number}",
+    tag = "group",
+    summary = "从分组移除设备",
+    description = "需要 ADMIN 及以上角色",
+    params(
+        ("id" = u64, Path, description = "分组 ID"),
+        ("device_number" = String, Path, description = "设备编号"),
+    ),
+    responses(
+        (status = 200, description = "成功", body = ApiResponse<Empty>),
+        (status = 401, description = "未登录或凭证无效", body = ErrorResponse),
+        (status = 403, description = "角色权限不足", body = ErrorResponse),
+        (status = 404, description = "资源不存在", body = ErrorResponse),
+    ),
+    security(("bearer" = []), ("api_key" = [])),
+)]
+pub async fn remove_group_device(
+    Path((id, device_number)): Path<(u64, String)>,
+) -> Result<ApiResponse<Empty>, AppError> {
+    info!("Remove device {} from group {}", device_number, id);
+
+    match GroupService::remove_device(id, &device_number).await {
+        Ok(_) => Ok(ApiResponse::new(Empty {})),
+        Err(e) => Err(AppError::from(e)),
+    }
+}
@@ -0,0 +1,139 @@
+use crate::params::requests::uv_lamp_notify_job::ListNotifyJobsParams;
+use crate::params::responses::common::{ApiResponse, PageResponse};
+use crate::params::responses::uv_lamp_notify_job::{
+    NotifyJobAttemptResponse, NotifyJobDetailResponse, NotifyJobResponse,
+};
+use crate::services::auth::auth_service::CurrentUser;
+use crate::services::uv_lamp::notify_job_service::NotifyJobService;
+use crate::utils::error::{AppError, ErrorResponse};
+use axum::extract::{Path, Query};
+use axum::Extension;
+use chrono_tz::Tz;
+use std::sync::Arc;
+use tracing::info;
+
+#[utoipa::path(
+    get,
+    path = "/uv_lamp/notify_jobs",
+    tag = "notify_job",
+    summary = "通知任务列表",
+    description = "需要 ADMIN 及以上角色",
+    params(
+        ListNotifyJobsParams,
+    ),
+    responses(
+        (status = 200, description = "成功", body = ApiResponse<PageResponse<NotifyJobResponse>>),
+        (status = 400, description = "参数错误", body = ErrorResponse),
+        (status = 401, description = "未登录或凭证无效", body = ErrorResponse),
+        (status = 403, description = "角色权限不足", body = ErrorResponse),
+    ),
+    security(("bearer" = []), ("api_key" = [])),
+)]
+pub async fn list_notify_jobs(
+    Extension(timezone): Extension<Arc<Tz>>,
+    Query(params): Query<ListNotifyJobsParams>,
+) -> Result<ApiResponse<PageResponse<NotifyJobResponse>>, AppError> {
+    match NotifyJobService::list(params).await {
+        Ok((jobs, total, pagination)) => {
+            let items = jobs
+                .into_iter()
+                .map(|job| NotifyJobResponse::from_job(job, &timezone))
+                .collect();
+            Ok(ApiResponse::new(PageResponse::new(items, total, pagination)))
+        }
+        Err(e) => Err(AppError::from(e)),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/uv_lamp/notify_jobs/{id}",
+    tag = "notify_job",
+    summary = "通知任务详情及发送记录",
+    description = "需要 ADMIN 及以上角色",
+    params(
+        ("id" = u64, Path, description = "通知任务 ID"),
+    ),
+    responses(
+        (status = 200, description = "成功", body = ApiResponse<NotifyJobDetailResponse>),
+        (status = 401, description = "未登录或凭证无效", body = ErrorResponse),
+        (status = 403, description = "角色权限不足", body = ErrorResponse),
+        (status = 404, description = "资源不存在", body = ErrorResponse),
+    ),
+    security(("bearer" = []), ("api_key" = [])),
+)]
+pub async fn show_notify_job(
+    Extension(timezone): Extension<Arc<Tz>>,
+    Path(id): Path<u64>,
+) -> Result<ApiResponse<NotifyJobDetailResponse>, AppError> {
+    match NotifyJobService::find_with_attempts(id).await {
+        Ok((job, attempts)) => Ok(ApiResponse::new(NotifyJobDetailResponse {
+            job: NotifyJobResponse::from_job(job, &timezone),
+            attempts: attempts
+                .into_iter()
+                .map(|attempt| NotifyJobAttemptResponse::from_attempt(attempt, &timezone))
+                .collect(),
+        })),
+        Err(e) => Err(AppError::from(e)),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/uv_lamp/notify_jobs/{id}/retry",
+    tag = "notify_job",
+    summary = "重新发送失败的通知任务",
+    description = "需要 ADMIN 及以上角色",
+    params(
+        ("id" = u64, Path, description = "通知任务 ID"),
+    ),
+    responses(
+        (status = 200, description = "成功", body = ApiResponse<NotifyJobResponse>),
+        (status = 401, description = "未登录或凭证无效", body = ErrorResponse),
+        (status = 403, description = "角色权限不足", body = ErrorResponse),
+        (status = 404, description = "资源不存在", body = ErrorResponse),
+    ),
+    security(("bearer" = []), ("api_key" = [])),
+)]
+pub async fn retry_notify_job(
+    Extension(timezone): Extension<Arc<Tz>>,
+    Extension(user): Extension<CurrentUser>,
+    Path(id): Path<u64>,
+) -> Result<ApiResponse<NotifyJobResponse>, AppError> {
+    info!("Retry notify job {}, by {}", id, user.username);
+
+    match NotifyJobService::retry(id).await {
+        Ok(job) => Ok(ApiResponse::new(NotifyJobResponse::from_job(job, &timezone))),
+        Err(e) => Err(AppError::from(e)),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/uv_lamp/notify_jobs/{id}/cancel",
+    tag = "notify_job",
+    summary = "取消未完成的通知任务",
+    description = "需要 ADMIN 及以上角色",
+    params(
+        ("id" = u64, Path, description = "通知任务 ID"),
+    ),
+    responses(
+        (status = 200, description = "成功", body = ApiResponse<NotifyJobResponse>),
+        (status = 401, description = "未登录或凭证无效", body = ErrorResponse),
+        (status = 403, description = "角色权限不足", body = ErrorResponse),
+        (status = 404, description = "资源不存在", body = ErrorResponse),
+    ),
+    security(("bearer" = []), ("api_key" = [])),
+)]
+pub async fn cancel_notify_job(
+    Extension(timezone): Extension<Arc<Tz>>,
+    Extension(user): Extension<CurrentUser>,
+    Path(id): Path<u64>,
+) -> Result<ApiResponse<NotifyJobResponse>, AppError> {
+    info!("Cancel notify job {}, by {}", id, user.username);
+
+    match NotifyJobService::cancel(id).await {
+        Ok(job) => Ok(ApiResponse::new(NotifyJobResponse::from_job(job, &timezone))),
+        Err(e) => Err(AppError::from(e)),
+    }
+}
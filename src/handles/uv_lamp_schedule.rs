@@ -0,0 +1,162 @@
+use crate::params::requests::uv_lamp_schedule::ScheduleParams;
+use crate::params::responses::common::{ApiResponse, Empty};
+use crate::params::responses::uv_lamp_schedule::ScheduleResponse;
+use crate::services::uv_lamp::schedule_service::ScheduleService;
+use crate::utils::error::{AppError, ErrorResponse};
+use axum::extract::Path;
+use axum::{Extension, Json};
+use chrono_tz::Tz;
+use std::sync::Arc;
+use tracing::info;
+use validator::Validate;
+
+#[utoipa::path(
+    post,
+    path = "/uv_lamp/schedules",
+    tag = "schedule",
+    summary = "新建消毒计划",
+    description = "需要 ADMIN 及以上角色",
+    request_body = ScheduleParams,
+    responses(
+        (status = 200, description = "成功", body = ApiResponse<ScheduleResponse>),
+        (status = 400, description = "参数错误", body = ErrorResponse),
+        (status = 401, description = "未登录或凭证无效", body = ErrorResponse),
+        (status = 403, description = "角色权限不足", body = ErrorResponse),
+    ),
+    security(("bearer" = []), ("api_key" = [])),
+)]
+pub async fn create_schedule(
+    Extension(timezone): Extension<Arc<Tz>>,
+    Json(params): Json<ScheduleParams>,
+) -> Result<ApiResponse<ScheduleResponse>, AppError> {
+    if let Err(e) = params.validate() {
+        return Err(AppError::from(e));
+    }
+    info!("Create disinfection schedule: {:?}", params);
+
+    match ScheduleService::create(params).await {
+        Ok(schedule) => Ok(ApiResponse::new(ScheduleResponse::from_schedule(
+            schedule, &timezone,
+        ))),
+        Err(e) => Err(AppError::from(e)),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/uv_lamp/schedules",
+    tag = "schedule",
+    summary = "消毒计划列表",
+    description = "需要 VIEWER 及以上角色",
+    responses(
+        (status = 200, description = "成功", body = ApiResponse<Vec<ScheduleResponse>>),
+        (status = 401, description = "未登录或凭证无效", body = ErrorResponse),
+        (status = 403, description = "角色权限不足", body = ErrorResponse),
+    ),
+    security(("bearer" = []), ("api_key" = [])),
+)]
+pub async fn list_schedules(
+    Extension(timezone): Extension<Arc<Tz>>,
+) -> Result<ApiResponse<Vec<ScheduleResponse>>, AppError> {
+    match ScheduleService::list().await {
+        Ok(schedules) => Ok(ApiResponse::new(
+            schedules
+                .into_iter()
+                .map(|schedule| ScheduleResponse::from_schedule(schedule, &timezone))
+                .collect(),
+        )),
+        Err(e) => Err(AppError::from(e)),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/uv_lamp/schedules/{id}",
+    tag = "schedule",
+    summary = "消毒计划详情",
+    description = "需要 VIEWER 及以上角色",
+    params(
+        ("id" = u64, Path, description = "消毒计划 ID"),
+    ),
+    responses(
+        (status = 200, description = "成功", body = ApiResponse<ScheduleResponse>),
+        (status = 401, description = "未登录或凭证无效", body = ErrorResponse),
+        (status = 403, description = "角色权限不足", body = ErrorResponse),
+        (status = 404, description = "资源不存在", body = ErrorResponse),
+    ),
+    security(("bearer" = []), ("api_key" = [])),
+)]
+pub async fn show_schedule(
+    Extension(timezone): Extension<Arc<Tz>>,
+    Path(id): Path<u64>,
+) -> Result<ApiResponse<ScheduleResponse>, AppError> {
+    match ScheduleService::find(id).await {
+        Ok(schedule) => Ok(ApiResponse::new(ScheduleResponse::from_schedule(
+            schedule, &timezone,
+        ))),
+        Err(e) => Err(AppError::from(e)),
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/uv_lamp/schedules/{id}",
+    tag = "schedule",
+    summary = "修改消毒计划",
+    description = "需要 ADMIN 及以上角色",
+    params(
+        ("id" = u64, Path, description = "消毒计划 ID"),
+    ),
+    request_body = ScheduleParams,
+    responses(
+        (status = 200, description = "成功", body = ApiResponse<ScheduleResponse>),
+        (status = 400, description = "参数错误", body = ErrorResponse),
+        (status = 401, description = "未登录或凭证无效", body = ErrorResponse),
+        (status = 403, description = "角色权限不足", body = ErrorResponse),
+        (status = 404, description = "资源不存在", body = ErrorResponse),
+    ),
+    security(("bearer" = []), ("api_key" = [])),
+)]
+pub async fn update_schedule(
+    Extension(timezone): Extension<Arc<Tz>>,
+    Path(id): Path<u64>,
+    Json(params): Json<ScheduleParams>,
+) -> Result<ApiResponse<ScheduleResponse>, AppError> {
+    if let Err(e) = params.validate() {
+        return Err(AppError::from(e));
+    }
+    info!("Update disinfection schedule {}: {:?}", id, params);
+
+    match ScheduleService::update(id, params).await {
+        Ok(schedule) => Ok(ApiResponse::new(ScheduleResponse::from_schedule(
+            schedule, &timezone,
+        ))),
+        Err(e) => Err(AppError::from(e)),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/uv_lamp/schedules/{id}",
+    tag = "schedule",
+    summary = "删除消毒计划",
+    description = "需要 ADMIN 及以上角色",
+    params(
+        ("id" = u64, Path, description = "消毒计划 ID"),
+    ),
+    responses(
+        (status = 200, description = "成功", body = ApiResponse<Empty>),
+        (status = 401, description = "未登录或凭证无效", body = ErrorResponse),
+        (status = 403, description = "角色权限不足", body = ErrorResponse),
+        (status = 404, description = "资源不存在", body = ErrorResponse),
+    ),
+    security(("bearer" = []), ("api_key" = [])),
+)]
+pub async fn delete_schedule(Path(id): Path<u64>) -> Result<ApiResponse<Empty>, AppError> {
+    info!("Delete disinfection schedule {}", id);
+
+    match ScheduleService::delete(id).await {
+        Ok(_) => Ok(ApiResponse::new(Empty {})),
+        Err(e) => Err(AppError::from(e)),
+    }
+}
@@ -1,32 +1,35 @@
+use crate::middlewares::auth::auth;
+use crate::middlewares::metrics::track_metrics;
+use crate::middlewares::request_id::request_id;
+use crate::routes::auth::{register_auth_admin_routes, register_auth_routes};
+use crate::routes::docs::register_docs_routes;
+use crate::routes::health::register_health_routes;
+use crate::routes::metrics::register_metrics_routes;
 use crate::routes::uv_lamp::register_uv_lamp_routes;
-use axum::http::StatusCode;
+use crate::utils::error::AppError;
+use axum::extract::Request as ExtractRequest;
 use axum::response::{IntoResponse, Response};
-use axum::{extract::Request as ExtractRequest, Json as AxumJson};
 use axum::{middleware, Extension, Router};
 use chrono_tz::Tz;
-use serde_json::json;
 use std::sync::Arc;
 
 pub fn init_routes(shared_timezone: Arc<Tz>) -> Router {
     Router::new()
-        .merge(register_uv_lamp_routes())
+        .merge(register_docs_routes())
+        .merge(register_health_routes())
+        .merge(register_metrics_routes())
+        .merge(register_auth_routes())
+        .merge(register_auth_admin_routes().route_layer(middleware::from_fn(auth)))
+        .merge(register_uv_lamp_routes().route_layer(middleware::from_fn(auth)))
         .layer(Extension(shared_timezone))
+        .layer(middleware::from_fn(track_metrics))
         .layer(middleware::from_fn(error_handler))
+        .layer(middleware::from_fn(request_id))
 }
 
 async fn error_handler(req: ExtractRequest, next: middleware::Next) -> Response {
     match req.extensions().get::<anyhow::Error>() {
-        Some(err) => {
-            let status_code = StatusCode::INTERNAL_SERVER_ERROR;
-            let error_message = err.to_string();
-            (
-                status_code,
-                AxumJson(json!({
-                    "message": error_message,
-                })),
-            )
-                .into_response()
-        }
+        Some(err) => AppError::Internal(err.to_string()).into_response(),
         None => next.run(req).await,
     }
 }
@@ -1,15 +1,18 @@
 use crate::cron::check_lamp_status::handle as check_lamp_status;
 use crate::cron::lamp_check_offline::handle as check_offline;
-use crate::cron::cron_task_manager::CronTaskManager;
+use crate::cron::cron_task_manager::{init_cron_task_manager, CronTaskManager};
+use crate::cron::disinfection_schedule;
+use crate::cron::idempotency_key_cleanup::handle as cleanup_idempotency_keys;
 use crate::tasks::mqtt_tasks;
-use crate::tasks::task_manager::TaskManager;
+use crate::tasks::task_manager::{init_task_manager, TaskManager};
+use chrono_tz::Tz;
 use std::sync::Arc;
 use tokio::sync::Notify;
 use tracing::{event, Level};
 use crate::tasks;
 
 pub async fn init_tasks(notify: Arc<Notify>) {
-    let task_manager = TaskManager::new(notify);
+    let task_manager = init_task_manager(TaskManager::new(notify));
     task_manager.register_task(mqtt_tasks::notify).await;
     task_manager.register_task(tasks::mqtt_status_tasks::notify).await;
     task_manager.start_tasks().await;
@@ -17,8 +20,8 @@ pub async fn init_tasks(notify: Arc<Notify>) {
     event!(Level::INFO, "tasks initialized");
 }
 
-pub async fn init_cron_tasks() {
-    let task_manager = CronTaskManager::new();
+pub async fn init_cron_tasks(timezone: Arc<Tz>) {
+    let task_manager = init_cron_task_manager(CronTaskManager::with_timezone(*timezone));
 
     task_manager.register_task(
         "UV device network status check".to_string(),
@@ -32,5 +35,13 @@ pub async fn init_cron_tasks() {
         Arc::new(|| check_offline()),
     ).await;
 
+    task_manager.register_task(
+        "Idempotency key cleanup".to_string(),
+        "0 */10 * * * *",
+        Arc::new(|| cleanup_idempotency_keys()),
+    ).await;
+
+    disinfection_schedule::load_all(&task_manager).await;
+
     task_manager.start().await
 }
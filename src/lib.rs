@@ -1,7 +1,9 @@
 pub mod cron;
 pub mod handles;
 pub mod init;
+pub mod middlewares;
 pub mod params;
+pub mod protocol;
 pub mod repositories;
 pub mod routes;
 pub mod services;
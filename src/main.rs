@@ -1,5 +1,6 @@
 use chrono_tz::Tz;
 use connect_x::init::tasks::init_cron_tasks;
+use connect_x::services::auth::auth_service::AuthService;
 use connect_x::init::{init_config, init_logging, init_routes, init_tasks};
 use connect_x::utils;
 use std::sync::Arc;
@@ -25,12 +26,20 @@ async fn main() {
     // application exits.
     let log_guard = init_logging(shared_timezone.clone());
 
+    if let Err(e) = utils::metrics::init_metrics() {
+        event!(Level::ERROR, "failed to initialize metrics: {}", e);
+    }
+
+    if let Err(e) = AuthService::ensure_admin().await {
+        event!(Level::ERROR, "failed to initialize admin user: {}", e);
+    }
+
     utils::mqtt::init_mqtt_handler().await.unwrap();
     event!(Level::INFO, "mqtt handler initialized");
 
     let notify = Arc::new(Notify::new());
     init_tasks(notify.clone()).await;
-    init_cron_tasks().await;
+    init_cron_tasks(shared_timezone.clone()).await;
 
     let app = init_routes(shared_timezone);
 
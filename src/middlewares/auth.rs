@@ -0,0 +1,46 @@
+use crate::services::auth::api_key_service::ApiKeyService;
+use crate::services::auth::auth_service::AuthService;
+use crate::utils::error::AppError;
+use axum::extract::Request;
+use axum::http::header;
+use axum::middleware::Next;
+use axum::response::Response;
+use tracing::warn;
+
+pub const API_KEY_HEADER: &str = "X-Api-Key";
+
+/// 校验 `Authorization: Bearer <token>` 或 `X-Api-Key: <key>`，通过后把 `CurrentUser` 放入请求扩展
+pub async fn auth(mut req: Request, next: Next) -> Result<Response, AppError> {
+    let token = req
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(|token| token.trim().to_string());
+    let api_key = req
+        .headers()
+        .get(API_KEY_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(|key| key.trim().to_string());
+
+    let result = match (token, api_key) {
+        (Some(token), _) => AuthService::authenticate(&token).await,
+        (None, Some(api_key)) => ApiKeyService::authenticate(&api_key).await,
+        (None, None) => {
+            return Err(AppError::Unauthorized(
+                "Missing bearer token or API key".to_string(),
+            ))
+        }
+    };
+
+    match result {
+        Ok(user) => {
+            req.extensions_mut().insert(user);
+            Ok(next.run(req).await)
+        }
+        Err(e) => {
+            warn!("Authentication failed: {}", e);
+            Err(AppError::Unauthorized(format!("Unauthorized: {}", e)))
+        }
+    }
+}
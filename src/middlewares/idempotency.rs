@@ -0,0 +1,211 @@
+use crate::services::auth::auth_service::CurrentUser;
+use crate::services::idempotency::idempotency_service::{
+    Idempotency, IdempotencyService, LOCK_RENEW_INTERVAL,
+};
+use crate::utils::error::AppError;
+use axum::body::{to_bytes, Body};
+use axum::extract::Request;
+use axum::http::{header, HeaderMap, HeaderValue, StatusCode};
+use axum::middleware::Next;
+use axum::response::Response;
+use serde::Deserialize;
+use std::future::Future;
+use std::time::Duration;
+use tokio::time::{self, MissedTickBehavior};
+use tracing::{error, info, warn};
+
+pub const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+const REPLAYED_HEADER: &str = "idempotent-replayed";
+const MAX_KEY_LENGTH: usize = 128;
+const MAX_BODY_SIZE: usize = 64 * 1024;
+
+struct RequestKey {
+    key: String,
+    ttl: Duration,
+    // 客户端显式传入的 `Idempotency-Key`，参数不同时拒绝；按 `message_id` 生成的 key 参数不同时视为新请求
+    explicit: bool,
+}
+
+#[derive(Deserialize)]
+struct MessageKey {
+    message_id: i32,
+    device_number: Option<String>,
+    group_id: Option<u64>,
+}
+
+/// 相同 key 的重复请求直接返回首次请求的结果，不再调用处理函数。
+/// 未传 `Idempotency-Key` 时以请求体中的设备编号和 `message_id` 作为 key，只在较短的时间窗口内去重。
+/// key 按调用方隔离，需挂在 `auth` 之后。等待回复超时的结果只保留一个去重窗口，之后允许重试
+pub async fn idempotency(req: Request, next: Next) -> Result<Response, AppError> {
+    let principal = match req.extensions().get::<CurrentUser>() {
+        Some(user) => user.principal(),
+        None => return Err(AppError::Unauthorized("Unauthorized".to_string())),
+    };
+    let (parts, body) = req.into_parts();
+    let bytes = to_bytes(body, MAX_BODY_SIZE)
+        .await
+        .map_err(|e| AppError::BadRequest(format!("Failed to read request body: {}", e)))?;
+    let Some(RequestKey { key, ttl, explicit }) = resolve_key(&parts.headers, &bytes)? else {
+        return Ok(next.run(Request::from_parts(parts, Body::from(bytes))).await);
+    };
+
+    let path = parts.uri.path().to_string();
+    let fingerprint = format!(
+        "{:x}",
+        md5::compute([parts.uri.query().unwrap_or("").as_bytes(), &bytes].concat())
+    );
+    let id = match IdempotencyService::begin(&principal, &path, &key, &fingerprint, ttl, !explicit).await? {
+        Idempotency::Started(id) => id,
+        Idempotency::Replay {
+            status_code,
+            response,
+        } => {
+            info!(
+                "Replay response of idempotency key {} on {} for {}",
+                key, path, principal
+            );
+            return Ok(replay(status_code, response));
+        }
+    };
+
+    let response = renew_while(id, next.run(Request::from_parts(parts, Body::from(bytes)))).await;
+    let (parts, body) = response.into_parts();
+    let bytes = match to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            abandon(id).await;
+            return Err(AppError::Internal(format!("Failed to read response body: {}", e)));
+        }
+    };
+    // 超时时指令已经发出，同样保留结果，避免立即重试导致重复下发
+    if parts.status.is_success() || parts.status == StatusCode::GATEWAY_TIMEOUT {
+        let response = String::from_utf8_lossy(&bytes);
+        let keep_for = (parts.status == StatusCode::GATEWAY_TIMEOUT).then(message_id_window);
+        if let Err(e) =
+            IdempotencyService::complete(id, parts.status.as_u16(), &response, keep_for).await
+        {
+            error!("Failed to save response of idempotency key {}: {}", key, e);
+        }
+    } else {
+        abandon(id).await;
+    }
+    Ok(Response::from_parts(parts, Body::from(bytes)))
+}
+
+// 处理期间定时续期，请求被取消时续期随之停止
+async fn renew_while<F: Future<Output = Response>>(id: u64, future: F) -> Response {
+    tokio::pin!(future);
+    let mut interval = time::interval_at(
+        time::Instant::now() + LOCK_RENEW_INTERVAL,
+        LOCK_RENEW_INTERVAL,
+    );
+    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    loop {
+        tokio::select! {
+            response = &mut future => return response,
+            _ = interval.tick() => {
+                if let Err(e) = IdempotencyService::renew(id).await {
+                    warn!("Failed to renew idempotency key {}: {}", id, e);
+                }
+            }
+        }
+    }
+}
+
+fn resolve_key(headers: &HeaderMap, body: &[u8]) -> Result<Option<RequestKey>, AppError> {
+    if let Some(value) = headers.get(IDEMPOTENCY_KEY_HEADER) {
+        let key = value
+            .to_str()
+            .map(str::trim)
+            .map_err(|_| AppError::BadRequest("Invalid Idempotency-Key header".to_string()))?;
+        if key.is_empty() || key.len() > MAX_KEY_LENGTH {
+            return Err(AppError::BadRequest(format!(
+                "Idempotency-Key must be 1 to {} characters",
+                MAX_KEY_LENGTH
+            )));
+        }
+        return Ok(Some(RequestKey {
+            key: key.to_string(),
+            ttl: read_seconds("UV_LAMP_IDEMPOTENCY_TTL", 24 * 60 * 60),
+            explicit: true,
+        }));
+    }
+
+    let window = message_id_window();
+    if window.is_zero() {
+        return Ok(None);
+    }
+    let Ok(message) = serde_json::from_slice::<MessageKey>(body) else {
+        return Ok(None);
+    };
+    // 按分组下发时以分组 ID 代替设备编号
+    let target = match (message.device_number, message.group_id) {
+        (Some(device_number), None) => device_number,
+        (None, Some(group_id)) => format!("group:{}", group_id),
+        _ => return Ok(None),
+    };
+    Ok(Some(RequestKey {
+        key: format!("message:{}:{}", target, message.message_id),
+        ttl: window,
+        explicit: false,
+    }))
+}
+
+fn message_id_window() -> Duration {
+    read_seconds("UV_LAMP_MESSAGE_ID_DEDUPE_WINDOW", 10 * 60)
+}
+
+fn read_seconds(key: &str, default: u64) -> Duration {
+    let seconds = std::env::var(key)
+        .ok()
+        .and_then(|value| value.parse::<u64>().ok())
+        .unwrap_or(default);
+    Duration::from_secs(seconds)
+}
+
+fn replay(status_code: u16, response: String) -> Response {
+    let mut response = Response::new(Body::from(response));
+    *response.status_mut() = StatusCode::from_u16(status_code).unwrap_or(StatusCode::OK);
+    let headers = response.headers_mut();
+    headers.insert(header::CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    headers.insert(REPLAYED_HEADER, HeaderValue::from_static("true"));
+    response
+}
+
+async fn abandon(id: u64) {
+    if let Err(e) = IdempotencyService::abandon(id).await {
+        error!("Failed to release idempotency key {}: {}", id, e);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{resolve_key, IDEMPOTENCY_KEY_HEADER};
+    use axum::http::{HeaderMap, HeaderValue};
+
+    #[test]
+    fn test_resolve_key() {
+        let body = br#"{"message_id":123456,"device_number":"867255071234567","status":true,"duration":10}"#;
+
+        let mut headers = HeaderMap::new();
+        headers.insert(IDEMPOTENCY_KEY_HEADER, HeaderValue::from_static(" retry-1 "));
+        let key = resolve_key(&headers, body).unwrap().unwrap();
+        assert_eq!(key.key, "retry-1");
+        assert!(key.explicit);
+
+        let key = resolve_key(&HeaderMap::new(), body).unwrap().unwrap();
+        assert_eq!(key.key, "message:867255071234567:123456");
+        assert!(!key.explicit);
+
+        let body = br#"{"message_id":123456,"group_id":3,"status":true,"duration":10}"#;
+        let key = resolve_key(&HeaderMap::new(), body).unwrap().unwrap();
+        assert_eq!(key.key, "message:group:3:123456");
+
+        // 批量开关没有 message_id，不传 key 时不去重
+        let body = br#"{"device_numbers":["867255071234567"],"status":true,"duration":10}"#;
+        assert!(resolve_key(&HeaderMap::new(), body).unwrap().is_none());
+
+        headers.insert(IDEMPOTENCY_KEY_HEADER, HeaderValue::from_static(""));
+        assert!(resolve_key(&headers, body).is_err());
+    }
+}
@@ -0,0 +1,5 @@
+pub mod auth;
+pub mod idempotency;
+pub mod metrics;
+pub mod request_id;
+pub mod role;
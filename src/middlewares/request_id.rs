@@ -0,0 +1,29 @@
+use crate::utils::request_id::{self, REQUEST_ID_HEADER};
+use axum::extract::Request;
+use axum::http::HeaderValue;
+use axum::middleware::Next;
+use axum::response::Response;
+use tracing::{info_span, Instrument};
+
+/// 读取或生成 `X-Request-Id`，请求处理期间的日志都带上该 ID，并原样写回响应头
+pub async fn request_id(req: Request, next: Next) -> Response {
+    let id = request_id::accept_or_generate(
+        req.headers()
+            .get(REQUEST_ID_HEADER)
+            .and_then(|value| value.to_str().ok()),
+    );
+    let span = info_span!(
+        "request",
+        request_id = %id,
+        method = %req.method(),
+        path = %req.uri().path(),
+    );
+
+    let mut response = request_id::scope(Some(id.clone()), next.run(req))
+        .instrument(span)
+        .await;
+    if let Ok(value) = HeaderValue::from_str(&id) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    response
+}
@@ -0,0 +1,34 @@
+use crate::services::auth::auth_service::{CurrentUser, Role};
+use crate::utils::error::AppError;
+use axum::extract::{Request, State};
+use axum::middleware::Next;
+use axum::response::Response;
+use tracing::warn;
+
+/// 要求调用方角色不低于 `required`，需挂在 `auth` 之后
+pub async fn require_role(
+    State(required): State<Role>,
+    req: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    let user = match req.extensions().get::<CurrentUser>() {
+        Some(user) => user,
+        None => return Err(AppError::Unauthorized("Unauthorized".to_string())),
+    };
+
+    if user.role < required {
+        warn!(
+            "{} with role {} denied access to {} {}, requires {}",
+            user.username,
+            user.role,
+            req.method(),
+            req.uri().path(),
+            required
+        );
+        return Err(AppError::Forbidden(format!(
+            "Forbidden: requires {} role",
+            required
+        )));
+    }
+    Ok(next.run(req).await)
+}
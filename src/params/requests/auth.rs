@@ -0,0 +1,56 @@
+use crate::services::auth::auth_service::Role;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+#[derive(Deserialize, Serialize, Validate, ToSchema)]
+pub struct LoginParams {
+    #[validate(length(min = 1, max = 64))]
+    #[schema(min_length = 1, max_length = 64)]
+    pub username: String,
+
+    #[validate(length(min = 1, max = 128))]
+    #[schema(min_length = 1, max_length = 128)]
+    pub password: String,
+}
+
+fn default_api_key_role() -> Role {
+    Role::Operator
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate, ToSchema)]
+pub struct IssueApiKeyParams {
+    #[validate(length(min = 1, max = 128))]
+    #[schema(min_length = 1, max_length = 128)]
+    pub name: String,
+
+    /// 不传默认为操作员
+    #[serde(default = "default_api_key_role")]
+    pub role: Role,
+
+    /// 有效天数，不传表示永不过期
+    #[validate(range(min = 1, max = 3650))]
+    #[schema(minimum = 1, maximum = 3650)]
+    pub expires_in_days: Option<u32>,
+}
+
+#[derive(Deserialize, Serialize, Validate, ToSchema)]
+pub struct CreateUserParams {
+    #[validate(length(min = 1, max = 64))]
+    #[schema(min_length = 1, max_length = 64)]
+    pub username: String,
+
+    #[validate(length(min = 8, max = 128))]
+    #[schema(min_length = 8, max_length = 128)]
+    pub password: String,
+
+    pub role: Role,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct UpdateUserParams {
+    pub role: Role,
+
+    #[serde(default)]
+    pub is_disabled: bool,
+}
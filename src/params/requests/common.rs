@@ -1,8 +1,39 @@
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 use validator::Validate;
 
-#[derive(Deserialize, Serialize, Validate)]
+#[derive(Deserialize, Serialize, Validate, ToSchema)]
 pub struct IdParams {
     #[validate(range(min = 1))]
+    #[schema(minimum = 1)]
     pub id: i32,
 }
+
+const DEFAULT_PAGE_SIZE: u32 = 20;
+const MAX_PAGE_SIZE: u32 = 100;
+
+/// 分页参数，页码从 1 开始，每页数量限制在 1 到 100 之间
+#[derive(Debug, Clone, Copy)]
+pub struct Pagination {
+    pub page: u32,
+    pub page_size: u32,
+}
+
+impl Pagination {
+    pub fn new(page: Option<u32>, page_size: Option<u32>) -> Self {
+        Pagination {
+            page: page.unwrap_or(1).max(1),
+            page_size: page_size
+                .unwrap_or(DEFAULT_PAGE_SIZE)
+                .clamp(1, MAX_PAGE_SIZE),
+        }
+    }
+
+    pub fn offset(&self) -> u64 {
+        (self.page as u64 - 1) * self.page_size as u64
+    }
+
+    pub fn limit(&self) -> u64 {
+        self.page_size as u64
+    }
+}
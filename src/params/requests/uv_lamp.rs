@@ -1,16 +1,158 @@
+use crate::utils::topic::TopicKind;
+use anyhow::anyhow;
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use utoipa::{IntoParams, ToSchema};
 use validator::Validate;
 
-#[derive(Debug, Deserialize, Serialize, Validate)]
+// 同步模式下最长等待设备回复的时间
+const MAX_WAIT: Duration = Duration::from_secs(60);
+
+// 批量开关展开分组后的最大设备数
+pub const MAX_BATCH_TARGETS: usize = 500;
+
+/// `device_number` 与 `group_id` 二选一，按分组开关时分组中的每台设备都使用同一个 `message_id`
+#[derive(Debug, Deserialize, Serialize, Validate, ToSchema)]
 pub struct TurnParams {
     #[validate(range(min = 100_000, max = 999_999))]
+    #[schema(minimum = 100_000, maximum = 999_999)]
     pub message_id: i32,
 
     #[validate(length(min = 12, max = 18))]
-    pub device_number: String,
+    #[schema(min_length = 12, max_length = 18)]
+    pub device_number: Option<String>,
+
+    /// 分组 ID，分组及其下级分组中的设备都会收到指令
+    pub group_id: Option<u64>,
 
     pub status: bool,
 
-    // 消毒时间: 分钟
+    /// 消毒时间: 分钟
     pub duration: i32
 }
+
+#[derive(Debug, Deserialize, Serialize, Validate, ToSchema)]
+pub struct BatchTurnParams {
+    #[validate(length(max = 500))]
+    #[schema(max_items = 500)]
+    #[serde(default)]
+    pub device_numbers: Vec<String>,
+
+    /// 分组 ID，分组及其下级分组中的设备都会收到指令
+    pub group_id: Option<u64>,
+
+    pub status: bool,
+
+    /// 消毒时间: 分钟
+    pub duration: i32,
+}
+
+#[derive(Debug, Deserialize, Serialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct TurnQuery {
+    /// 同步等待设备回复的时间，如 `10s`、`500ms`，不传则发送后立即返回
+    pub wait: Option<String>,
+}
+
+impl TurnQuery {
+    pub fn wait_duration(&self) -> Result<Option<Duration>, anyhow::Error> {
+        let wait = match &self.wait {
+            Some(wait) => wait.trim(),
+            None => return Ok(None),
+        };
+        let duration = if let Some(millis) = wait.strip_suffix("ms") {
+            Duration::from_millis(millis.parse()?)
+        } else if let Some(seconds) = wait.strip_suffix('s') {
+            Duration::from_secs(seconds.parse()?)
+        } else {
+            Duration::from_secs(wait.parse()?)
+        };
+        if duration.is_zero() || duration > MAX_WAIT {
+            return Err(anyhow!(
+                "wait must be between 1ms and {}s",
+                MAX_WAIT.as_secs()
+            ));
+        }
+        Ok(Some(duration))
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ListCommandsParams {
+    pub device_number: Option<String>,
+
+    /// 只查询分组及其下级分组中设备的指令
+    pub group_id: Option<u64>,
+
+    pub message_id: Option<String>,
+
+    /// 按是否已被设备确认过滤
+    pub is_acked: Option<bool>,
+
+    pub page: Option<u32>,
+
+    pub page_size: Option<u32>,
+}
+
+#[derive(Debug, Deserialize, Serialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ListMessagesParams {
+    pub device_number: Option<String>,
+
+    /// 只查询分组及其下级分组中设备的消息
+    pub group_id: Option<u64>,
+
+    pub kind: Option<TopicKind>,
+
+    /// 起始时间(含)，RFC 3339 或本地时间 `YYYY-MM-DD HH:MM:SS`
+    pub start_time: Option<String>,
+
+    /// 结束时间(不含)，格式同 start_time
+    pub end_time: Option<String>,
+
+    /// 消息内容包含的文本
+    pub keyword: Option<String>,
+
+    pub page: Option<u32>,
+
+    pub page_size: Option<u32>,
+}
+
+#[derive(Debug, Deserialize, Serialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct StreamEventsParams {
+    /// 只推送该设备的事件
+    pub device_number: Option<String>,
+
+    /// 只推送分组及其下级分组中设备的事件，与 device_number 同时传时取并集
+    pub group_id: Option<u64>,
+}
+
+#[cfg(test)]
+mod test {
+    use super::TurnQuery;
+    use std::time::Duration;
+
+    fn wait(value: &str) -> Result<Option<Duration>, anyhow::Error> {
+        TurnQuery {
+            wait: Some(value.to_string()),
+        }
+        .wait_duration()
+    }
+
+    #[test]
+    fn test_wait_duration() {
+        assert_eq!(wait("10s").unwrap(), Some(Duration::from_secs(10)));
+        assert_eq!(wait("500ms").unwrap(), Some(Duration::from_millis(500)));
+        assert_eq!(wait("3").unwrap(), Some(Duration::from_secs(3)));
+        assert_eq!(TurnQuery { wait: None }.wait_duration().unwrap(), None);
+    }
+
+    #[test]
+    fn test_wait_duration_invalid() {
+        assert!(wait("0s").is_err());
+        assert!(wait("61s").is_err());
+        assert!(wait("ten").is_err());
+    }
+}
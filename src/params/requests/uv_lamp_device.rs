@@ -0,0 +1,61 @@
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use validator::Validate;
+
+#[derive(Debug, Deserialize, Serialize, Validate, ToSchema)]
+pub struct CreateDeviceParams {
+    #[validate(length(min = 12, max = 18))]
+    #[schema(min_length = 12, max_length = 18)]
+    pub device_number: String,
+
+    #[validate(length(max = 128))]
+    #[schema(max_length = 128)]
+    #[serde(default)]
+    pub name: String,
+
+    #[validate(length(max = 512))]
+    #[schema(max_length = 512)]
+    #[serde(default)]
+    pub remark: String,
+
+    /// 设备所属产品，必须是已接入的产品，不传时使用默认产品
+    #[validate(length(max = 64))]
+    #[schema(max_length = 64)]
+    pub product_key: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate, ToSchema)]
+pub struct UpdateDeviceParams {
+    #[validate(length(max = 128))]
+    #[schema(max_length = 128)]
+    #[serde(default)]
+    pub name: String,
+
+    #[validate(length(max = 512))]
+    #[schema(max_length = 512)]
+    #[serde(default)]
+    pub remark: String,
+
+    /// 设备所属产品，必须是已接入的产品，不传时使用默认产品
+    #[validate(length(max = 64))]
+    #[schema(max_length = 64)]
+    pub product_key: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ListDevicesParams {
+    /// 是否包含已停用的设备
+    #[serde(default)]
+    pub include_decommissioned: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ListDeviceStatusParams {
+    /// 按在线状态过滤，不传则返回全部
+    pub online: Option<bool>,
+
+    /// 只返回分组及其下级分组中的设备
+    pub group_id: Option<u64>,
+}
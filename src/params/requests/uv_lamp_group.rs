@@ -0,0 +1,52 @@
+use crate::repositories::uv_lamp_device_groups::GroupKind;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+fn default_kind() -> GroupKind {
+    GroupKind::Zone
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate, ToSchema)]
+pub struct CreateGroupParams {
+    /// 上级分组 ID，0 表示顶级分组
+    #[serde(default)]
+    pub parent_id: u64,
+
+    #[validate(length(min = 1, max = 128))]
+    #[schema(min_length = 1, max_length = 128)]
+    pub name: String,
+
+    #[serde(default = "default_kind")]
+    pub kind: GroupKind,
+
+    #[validate(length(max = 512))]
+    #[schema(max_length = 512)]
+    #[serde(default)]
+    pub remark: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate, ToSchema)]
+pub struct UpdateGroupParams {
+    #[serde(default)]
+    pub parent_id: u64,
+
+    #[validate(length(min = 1, max = 128))]
+    #[schema(min_length = 1, max_length = 128)]
+    pub name: String,
+
+    #[serde(default = "default_kind")]
+    pub kind: GroupKind,
+
+    #[validate(length(max = 512))]
+    #[schema(max_length = 512)]
+    #[serde(default)]
+    pub remark: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate, ToSchema)]
+pub struct GroupDevicesParams {
+    #[validate(length(min = 1, max = 500))]
+    #[schema(min_items = 1, max_items = 500)]
+    pub device_numbers: Vec<String>,
+}
@@ -0,0 +1,19 @@
+use crate::repositories::uv_lamp_mqtt_notify_job::JobState;
+use serde::{Deserialize, Serialize};
+use utoipa::IntoParams;
+
+#[derive(Debug, Deserialize, Serialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ListNotifyJobsParams {
+    pub state: Option<JobState>,
+
+    /// 任务类型，如 `LIGHT_SWITCH_TASK`、`LIGHT_STATUS_TASK`
+    #[serde(rename = "type")]
+    pub job_type: Option<String>,
+
+    pub device_number: Option<String>,
+
+    pub page: Option<u32>,
+
+    pub page_size: Option<u32>,
+}
@@ -0,0 +1,75 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use utoipa::ToSchema;
+use validator::Validate;
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum Weekday {
+    Mon,
+    Tue,
+    Wed,
+    Thu,
+    Fri,
+    Sat,
+    Sun,
+}
+
+impl Display for Weekday {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Weekday::Mon => write!(f, "MON"),
+            Weekday::Tue => write!(f, "TUE"),
+            Weekday::Wed => write!(f, "WED"),
+            Weekday::Thu => write!(f, "THU"),
+            Weekday::Fri => write!(f, "FRI"),
+            Weekday::Sat => write!(f, "SAT"),
+            Weekday::Sun => write!(f, "SUN"),
+        }
+    }
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// 新建和修改消毒计划共用的参数，`cron_expression` 与 `weekdays` + `time_of_day` 二选一，
+/// `device_number` 与 `group_id` 二选一
+#[derive(Debug, Deserialize, Serialize, Validate, ToSchema)]
+pub struct ScheduleParams {
+    #[validate(length(min = 1, max = 128))]
+    #[schema(min_length = 1, max_length = 128)]
+    pub name: String,
+
+    /// 秒 分 时 日 月 周 [年]，如 `0 0 22 * * *`
+    #[validate(length(max = 128))]
+    #[schema(max_length = 128)]
+    pub cron_expression: Option<String>,
+
+    /// 每周日历: 执行的星期
+    pub weekdays: Option<Vec<Weekday>>,
+
+    /// 每周日历: 执行时间，格式 HH:MM
+    pub time_of_day: Option<String>,
+
+    #[validate(length(min = 12, max = 18))]
+    #[schema(min_length = 12, max_length = 18)]
+    pub device_number: Option<String>,
+
+    pub group_id: Option<u64>,
+
+    pub status: bool,
+
+    /// 消毒时间: 分钟
+    #[validate(range(min = 0, max = 1440))]
+    #[schema(minimum = 0, maximum = 1440)]
+    pub duration: i32,
+
+    /// 时区，如 `Asia/Shanghai`，不传则使用服务默认时区
+    #[validate(length(max = 64))]
+    #[schema(max_length = 64)]
+    pub timezone: Option<String>,
+
+    #[serde(default = "default_enabled")]
+    pub is_enabled: bool,
+}
@@ -0,0 +1,82 @@
+use crate::repositories::api_keys::ApiKey;
+use crate::repositories::users::User;
+use crate::utils::datetime::format_datetime;
+use chrono_tz::Tz;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LoginResponse {
+    pub token: String,
+    pub token_type: String,
+}
+
+impl LoginResponse {
+    pub fn bearer(token: String) -> Self {
+        LoginResponse {
+            token,
+            token_type: "Bearer".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ApiKeyResponse {
+    pub id: u64,
+    pub name: String,
+    /// 密钥前缀，用于辨认密钥，不能用于认证
+    pub key_prefix: String,
+    pub role: String,
+    pub created_by: u64,
+    pub last_used_at: Option<String>,
+    pub expires_at: Option<String>,
+    pub revoked_at: Option<String>,
+    pub created_at: String,
+}
+
+impl ApiKeyResponse {
+    pub fn from_api_key(key: ApiKey, timezone: &Tz) -> Self {
+        ApiKeyResponse {
+            id: key.id,
+            name: key.name,
+            key_prefix: key.key_prefix,
+            role: key.role,
+            created_by: key.created_by,
+            last_used_at: key.last_used_at.map(|time| format_datetime(&time, timezone)),
+            expires_at: key.expires_at.map(|time| format_datetime(&time, timezone)),
+            revoked_at: key.revoked_at.map(|time| format_datetime(&time, timezone)),
+            created_at: format_datetime(&key.created_at, timezone),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct IssuedApiKeyResponse {
+    #[serde(flatten)]
+    pub api_key: ApiKeyResponse,
+    /// 密钥明文，只在创建时返回一次
+    pub key: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct UserResponse {
+    pub id: u64,
+    pub username: String,
+    pub role: String,
+    pub is_disabled: bool,
+    pub last_login_at: Option<String>,
+    pub created_at: String,
+}
+
+impl UserResponse {
+    pub fn from_user(user: User, timezone: &Tz) -> Self {
+        UserResponse {
+            id: user.id,
+            username: user.username,
+            role: user.role,
+            is_disabled: user.is_disabled == 1,
+            last_login_at: user.last_login_at.map(|time| format_datetime(&time, timezone)),
+            created_at: format_datetime(&user.created_at, timezone),
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use crate::params::requests::common::Pagination;
 use axum::{
     http::StatusCode,
     response::{IntoResponse, Response},
@@ -5,17 +6,37 @@ use axum::{
 };
 use serde::Serialize;
 use serde_json::json;
+use utoipa::ToSchema;
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct ApiResponse<T> {
     code: i32,
     message: String,
     data: Option<T>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct Empty {}
 
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PageResponse<T> {
+    pub items: Vec<T>,
+    pub total: i64,
+    pub page: u32,
+    pub page_size: u32,
+}
+
+impl<T> PageResponse<T> {
+    pub fn new(items: Vec<T>, total: i64, pagination: Pagination) -> Self {
+        PageResponse {
+            items,
+            total,
+            page: pagination.page,
+            page_size: pagination.page_size,
+        }
+    }
+}
+
 impl<T> ApiResponse<T> {
     pub fn new(data: T) -> Self {
         ApiResponse {
@@ -0,0 +1,50 @@
+use crate::services::health::health_service::{DependencyCheck, Readiness};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LivenessResponse {
+    /// 进程存活时固定为 `ok`
+    pub status: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DependencyStatus {
+    pub healthy: bool,
+    /// 不可用的原因
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ReadinessResponse {
+    /// 所有依赖都可用时为 true
+    pub ready: bool,
+    pub mysql: DependencyStatus,
+    /// MQTT 事件循环的连接状态
+    pub mqtt: DependencyStatus,
+    /// 回调通知等后台循环任务
+    pub tasks: DependencyStatus,
+    /// 在线检测、消毒计划等定时任务
+    pub cron_tasks: DependencyStatus,
+}
+
+impl DependencyStatus {
+    fn from_check(check: DependencyCheck) -> Self {
+        DependencyStatus {
+            healthy: check.is_healthy(),
+            reason: check.reason,
+        }
+    }
+}
+
+impl ReadinessResponse {
+    pub fn from_readiness(readiness: Readiness) -> Self {
+        ReadinessResponse {
+            ready: readiness.is_ready(),
+            mysql: DependencyStatus::from_check(readiness.mysql),
+            mqtt: DependencyStatus::from_check(readiness.mqtt),
+            tasks: DependencyStatus::from_check(readiness.tasks),
+            cron_tasks: DependencyStatus::from_check(readiness.cron_tasks),
+        }
+    }
+}
@@ -1 +1,8 @@
+pub mod auth;
 pub mod common;
+pub mod health;
+pub mod uv_lamp;
+pub mod uv_lamp_device;
+pub mod uv_lamp_group;
+pub mod uv_lamp_notify_job;
+pub mod uv_lamp_schedule;
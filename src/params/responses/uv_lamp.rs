@@ -0,0 +1,135 @@
+use crate::repositories::uv_lamp_mqtt_message::Message;
+use crate::repositories::uv_lamp_mqtt_received_messages::ReceivedMessage;
+use crate::services::uv_lamp::control_service::{BatchTurnResult, TurnReply};
+use crate::protocol::common::{LampStatus, Reason};
+use crate::utils::datetime::{format_datetime, format_timestamp};
+use crate::utils::mqtt_connection::{ConnectionState, ConnectionStatus};
+use crate::utils::topic::TopicKind;
+use chrono_tz::Tz;
+use serde::Serialize;
+use serde_json::Value;
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CommandResponse {
+    pub id: u64,
+    pub message_id: String,
+    pub device_number: String,
+    pub payload: Value,
+    /// 下发指令的接口请求 ID，即 `X-Request-Id`
+    pub request_id: Option<String>,
+    pub is_acked: bool,
+    pub acked_at: Option<String>,
+    /// 从下发指令到设备确认的耗时，单位毫秒
+    pub ack_latency_ms: Option<u32>,
+    pub created_at: String,
+}
+
+impl CommandResponse {
+    pub fn from_message(message: Message, timezone: &Tz) -> Self {
+        CommandResponse {
+            id: message.id,
+            message_id: message.message_id,
+            device_number: message.device_number,
+            payload: serde_json::from_str(&message.payload).unwrap_or(Value::String(message.payload)),
+            request_id: message.request_id,
+            is_acked: message.is_acked == 1,
+            acked_at: message.acked_at.map(|time| format_datetime(&time, timezone)),
+            ack_latency_ms: message.ack_latency_ms,
+            created_at: format_datetime(&message.created_at, timezone),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TurnReplyResponse {
+    pub message_id: i32,
+    /// 设备回复的灯状态: 0-空闲;1-关闭;2-检测;3-运行
+    #[schema(value_type = Option<u8>)]
+    pub status: Option<LampStatus>,
+    /// 切换到当前状态的原因: 1-状态更新;2-定时打开;3-定时关闭;4-平台打开;5-平台关闭;6-红外报警;7-红外报警解除;8-检测正常;9-非法灯管
+    #[schema(value_type = Option<u8>)]
+    pub reason: Option<Reason>,
+    /// 设备回复的原始内容
+    pub payload: Value,
+}
+
+impl From<TurnReply> for TurnReplyResponse {
+    fn from(reply: TurnReply) -> Self {
+        TurnReplyResponse {
+            message_id: reply.message_id,
+            status: reply.status,
+            reason: reply.reason,
+            payload: reply.payload,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BatchTurnResponse {
+    pub total: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub results: Vec<BatchTurnResult>,
+}
+
+impl From<Vec<BatchTurnResult>> for BatchTurnResponse {
+    fn from(results: Vec<BatchTurnResult>) -> Self {
+        let succeeded = results.iter().filter(|result| result.success).count();
+        BatchTurnResponse {
+            total: results.len(),
+            succeeded,
+            failed: results.len() - succeeded,
+            results,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ReceivedMessageResponse {
+    pub id: u64,
+    pub topic: String,
+    pub kind: Option<TopicKind>,
+    pub device_number: String,
+    /// 能解析为 JSON 时返回解析后的内容，否则返回原始字符串
+    pub payload: Value,
+    pub created_at: String,
+}
+
+impl ReceivedMessageResponse {
+    pub fn from_message(message: ReceivedMessage, timezone: &Tz) -> Self {
+        ReceivedMessageResponse {
+            id: message.id,
+            kind: TopicKind::from_segment(&message.kind),
+            topic: message.topic,
+            device_number: message.device_number,
+            payload: serde_json::from_str(&message.payload).unwrap_or(Value::String(message.payload)),
+            created_at: format_datetime(&message.created_at, timezone),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct MqttStatusResponse {
+    pub state: ConnectionState,
+    /// 进入当前状态的时间
+    pub since: Option<String>,
+    /// 连续重连失败的次数
+    pub reconnect_attempts: u32,
+    pub last_error: Option<String>,
+    pub last_connected_at: Option<String>,
+}
+
+impl MqttStatusResponse {
+    pub fn from_status(status: ConnectionStatus, timezone: &Tz) -> Self {
+        MqttStatusResponse {
+            state: status.state,
+            since: format_timestamp(status.since, timezone),
+            reconnect_attempts: status.reconnect_attempts,
+            last_error: status.last_error,
+            last_connected_at: status
+                .last_connected_at
+                .and_then(|time| format_timestamp(time, timezone)),
+        }
+    }
+}
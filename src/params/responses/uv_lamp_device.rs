@@ -0,0 +1,64 @@
+use crate::repositories::uv_lamp_devices::Device;
+use crate::services::uv_lamp::status_service::DeviceStatus;
+use crate::utils::datetime::{format_datetime, format_timestamp};
+use crate::utils::topic::get_topic_config;
+use chrono_tz::Tz;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DeviceResponse {
+    pub id: u64,
+    pub device_number: String,
+    pub name: String,
+    pub remark: String,
+    /// 设备所属产品，未指定时为默认产品
+    pub product_key: String,
+    pub is_decommissioned: bool,
+    pub decommissioned_at: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl DeviceResponse {
+    pub fn from_device(device: Device, timezone: &Tz) -> Self {
+        DeviceResponse {
+            id: device.id,
+            device_number: device.device_number,
+            name: device.name,
+            remark: device.remark,
+            product_key: get_topic_config()
+                .product_key_or_default(&device.product_key)
+                .to_string(),
+            is_decommissioned: device.is_decommissioned == 1,
+            decommissioned_at: device
+                .decommissioned_at
+                .map(|time| format_datetime(&time, timezone)),
+            created_at: format_datetime(&device.created_at, timezone),
+            updated_at: format_datetime(&device.updated_at, timezone),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DeviceStatusResponse {
+    pub device_number: String,
+    pub is_online: bool,
+    pub last_response_time: Option<String>,
+    pub last_query_time: Option<String>,
+}
+
+impl DeviceStatusResponse {
+    pub fn from_status(status: DeviceStatus, timezone: &Tz) -> Self {
+        DeviceStatusResponse {
+            device_number: status.device_number,
+            is_online: status.is_online,
+            last_response_time: status
+                .last_response_time
+                .and_then(|time| format_timestamp(time, timezone)),
+            last_query_time: status
+                .last_query_time
+                .and_then(|time| format_timestamp(time, timezone)),
+        }
+    }
+}
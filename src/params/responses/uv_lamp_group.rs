@@ -0,0 +1,38 @@
+use crate::repositories::uv_lamp_device_groups::Group;
+use crate::utils::datetime::format_datetime;
+use chrono_tz::Tz;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct GroupResponse {
+    pub id: u64,
+    pub parent_id: u64,
+    pub name: String,
+    pub kind: String,
+    pub remark: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl GroupResponse {
+    pub fn from_group(group: Group, timezone: &Tz) -> Self {
+        GroupResponse {
+            id: group.id,
+            parent_id: group.parent_id,
+            name: group.name,
+            kind: group.kind,
+            remark: group.remark,
+            created_at: format_datetime(&group.created_at, timezone),
+            updated_at: format_datetime(&group.updated_at, timezone),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct GroupDetailResponse {
+    #[serde(flatten)]
+    pub group: GroupResponse,
+    /// 分组及其下级分组中的全部在用设备
+    pub device_numbers: Vec<String>,
+}
@@ -0,0 +1,80 @@
+use crate::repositories::uv_lamp_mqtt_notify_job::{Job, JobState};
+use crate::repositories::uv_lamp_mqtt_notify_job_attempts::Attempt;
+use crate::utils::datetime::{format_datetime, format_timestamp};
+use chrono_tz::Tz;
+use serde::Serialize;
+use serde_json::Value;
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct NotifyJobResponse {
+    pub id: u64,
+    #[serde(rename = "type")]
+    pub job_type: String,
+    pub device_number: String,
+    /// 触发通知的指令对应的接口请求 ID
+    pub request_id: Option<String>,
+    pub state: JobState,
+    /// 能解析为 JSON 时返回解析后的内容，否则返回原始字符串
+    pub notify_contents: Value,
+    pub retry_count: u8,
+    pub next_retry_at: Option<String>,
+    pub cancelled_at: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl NotifyJobResponse {
+    pub fn from_job(job: Job, timezone: &Tz) -> Self {
+        let next_retry_at = match job.next_retry_time {
+            0 => None,
+            timestamp => format_timestamp(timestamp, timezone),
+        };
+        NotifyJobResponse {
+            id: job.id,
+            state: job.state(),
+            job_type: job.job_type,
+            device_number: job.device_number,
+            request_id: job.request_id,
+            notify_contents: serde_json::from_str(&job.notify_contents)
+                .unwrap_or(Value::String(job.notify_contents)),
+            retry_count: job.retry_count,
+            next_retry_at,
+            cancelled_at: job.deleted_at.map(|time| format_datetime(&time, timezone)),
+            created_at: format_datetime(&job.created_at, timezone),
+            updated_at: format_datetime(&job.updated_at, timezone),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct NotifyJobAttemptResponse {
+    pub id: u64,
+    pub attempt: u16,
+    pub is_success: bool,
+    /// 回调接口返回的 HTTP 状态码，请求未送达时为空
+    pub status_code: Option<u16>,
+    pub error: String,
+    pub created_at: String,
+}
+
+impl NotifyJobAttemptResponse {
+    pub fn from_attempt(attempt: Attempt, timezone: &Tz) -> Self {
+        NotifyJobAttemptResponse {
+            id: attempt.id,
+            attempt: attempt.attempt,
+            is_success: attempt.is_success == 1,
+            status_code: attempt.status_code,
+            error: attempt.error,
+            created_at: format_datetime(&attempt.created_at, timezone),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct NotifyJobDetailResponse {
+    #[serde(flatten)]
+    pub job: NotifyJobResponse,
+    /// 每次发送的结果，按时间先后排列
+    pub attempts: Vec<NotifyJobAttemptResponse>,
+}
@@ -0,0 +1,77 @@
+use crate::cron::disinfection_schedule;
+use crate::repositories::uv_lamp_schedules::Schedule;
+use crate::utils::datetime::format_datetime;
+use chrono::Utc;
+use chrono_tz::Tz;
+use serde::Serialize;
+use std::str::FromStr;
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ScheduleResponse {
+    pub id: u64,
+    pub name: String,
+    pub cron_expression: String,
+    pub weekdays: Vec<String>,
+    pub time_of_day: String,
+    pub device_number: Option<String>,
+    pub group_id: Option<u64>,
+    pub status: bool,
+    pub duration: i32,
+    pub timezone: String,
+    pub is_enabled: bool,
+    /// 下次执行时间，按计划自身的时区显示
+    pub next_run_at: Option<String>,
+    pub last_run_at: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl ScheduleResponse {
+    /// 未单独指定时区的计划按服务默认时区 `timezone` 计算下次执行时间
+    pub fn from_schedule(schedule: Schedule, timezone: &Tz) -> Self {
+        let schedule_timezone = disinfection_schedule::timezone(&schedule.timezone, *timezone).ok();
+        let next_run_at = disinfection_schedule::cron_expression(
+            &schedule.cron_expression,
+            &schedule.weekdays,
+            &schedule.time_of_day,
+        )
+        .ok()
+        .zip(schedule_timezone)
+        .filter(|_| schedule.is_enabled == 1)
+        .and_then(|(expression, schedule_timezone)| {
+            cron::Schedule::from_str(&expression)
+                .ok()?
+                .upcoming(schedule_timezone)
+                .next()
+        })
+        .map(|time| format_datetime(&time.with_timezone(&Utc), &time.timezone()));
+
+        ScheduleResponse {
+            id: schedule.id,
+            name: schedule.name,
+            cron_expression: schedule.cron_expression,
+            weekdays: schedule
+                .weekdays
+                .split(',')
+                .filter(|weekday| !weekday.is_empty())
+                .map(|weekday| weekday.to_string())
+                .collect(),
+            time_of_day: schedule.time_of_day,
+            device_number: (!schedule.device_number.is_empty()).then_some(schedule.device_number),
+            group_id: (schedule.group_id != 0).then_some(schedule.group_id),
+            status: schedule.status == 1,
+            duration: schedule.duration,
+            timezone: schedule_timezone
+                .map(|timezone| timezone.to_string())
+                .unwrap_or(schedule.timezone),
+            is_enabled: schedule.is_enabled == 1,
+            next_run_at,
+            last_run_at: schedule
+                .last_run_at
+                .map(|time| format_datetime(&time, timezone)),
+            created_at: format_datetime(&schedule.created_at, timezone),
+            updated_at: format_datetime(&schedule.updated_at, timezone),
+        }
+    }
+}
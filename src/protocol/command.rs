@@ -0,0 +1,107 @@
+use crate::protocol::common::{LampStatus, MessageId, Reason};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// `oc/s` 平台下发的开关指令
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Command {
+    pub id: MessageId,
+    /// 开灯为 1，关灯为 0
+    #[serde(rename = "s", with = "switch")]
+    pub turn_on: bool,
+    /// 消毒时间，单位分钟
+    #[serde(rename = "d")]
+    pub duration: i32,
+}
+
+/// `oc/c` 设备确认指令，带回指令中的 id；`up/c` 中的回复也按此解析
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CommandAck {
+    pub id: MessageId,
+    #[serde(rename = "s", default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<LampStatus>,
+    #[serde(rename = "c", default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<Reason>,
+}
+
+/// 分发回复时只需要的 id，`up/c` 中定时关灯、红外报警等主动上报没有 id
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Reply {
+    #[serde(default)]
+    pub id: Option<MessageId>,
+}
+
+mod switch {
+    use super::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S>(turn_on: &bool, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_u8(if *turn_on { 1 } else { 0 })
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<bool, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match u8::deserialize(deserializer)? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(serde::de::Error::custom("Invalid value for switch")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Command, CommandAck, Reply};
+    use crate::protocol::common::{LampStatus, MessageId, Reason};
+    use serde_json::Value;
+
+    fn round_trip<T>(payload: &str) -> T
+    where
+        T: serde::Serialize + serde::de::DeserializeOwned,
+    {
+        let message: T = serde_json::from_str(payload).unwrap();
+        let expected: Value = serde_json::from_str(payload).unwrap();
+        assert_eq!(serde_json::to_value(&message).unwrap(), expected);
+        message
+    }
+
+    #[test]
+    fn test_command() {
+        let command: Command = round_trip(r#"{"id":482913,"s":1,"d":30}"#);
+        assert_eq!(
+            command,
+            Command {
+                id: MessageId::Number(482913),
+                turn_on: true,
+                duration: 30,
+            }
+        );
+        let command: Command = round_trip(r#"{"id":482914,"s":0,"d":0}"#);
+        assert!(!command.turn_on);
+        assert!(serde_json::from_str::<Command>(r#"{"id":1,"s":2,"d":0}"#).is_err());
+    }
+
+    #[test]
+    fn test_command_ack() {
+        let ack: CommandAck = round_trip(r#"{"id":"482913"}"#);
+        assert_eq!(ack.id.to_string(), "482913");
+        assert_eq!(ack.status, None);
+
+        let ack: CommandAck = round_trip(r#"{"id":482913,"s":3,"c":4}"#);
+        assert_eq!(ack.status, Some(LampStatus::Running));
+        assert_eq!(ack.reason, Some(Reason::PlatformOpen));
+    }
+
+    #[test]
+    fn test_reply() {
+        let reply: Reply = serde_json::from_str(r#"{"id":482913,"s":3,"c":4}"#).unwrap();
+        assert_eq!(reply.id, Some(MessageId::Number(482913)));
+        let reply: Reply =
+            serde_json::from_str(r#"{"s":1,"u":0,"d":0,"ts":"2024-05-20 10:45:30","c":3}"#)
+                .unwrap();
+        assert!(reply.id.is_none());
+    }
+}
@@ -0,0 +1,178 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+
+/// 消息 ID，平台下发时为数字或字符串，设备回复时原样带回
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum MessageId {
+    Number(u64),
+    Text(String),
+}
+
+impl fmt::Display for MessageId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MessageId::Number(id) => write!(f, "{}", id),
+            MessageId::Text(id) => f.write_str(id),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct Envelope {
+    id: MessageId,
+}
+
+/// 读取任意 payload 中的消息 ID，用于匹配回复对应的指令
+pub fn message_id(payload: &str) -> Option<MessageId> {
+    serde_json::from_str::<Envelope>(payload)
+        .ok()
+        .map(|envelope| envelope.id)
+}
+
+/// 灯状态: 0-空闲;1-关闭;2-检测;3-运行
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LampStatus {
+    Free,
+    Off,
+    Check,
+    Running,
+}
+
+impl LampStatus {
+    pub fn as_int(&self) -> u8 {
+        match self {
+            LampStatus::Free => 0,
+            LampStatus::Off => 1,
+            LampStatus::Check => 2,
+            LampStatus::Running => 3,
+        }
+    }
+}
+
+impl Serialize for LampStatus {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_u8(self.as_int())
+    }
+}
+
+impl<'de> Deserialize<'de> for LampStatus {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = u8::deserialize(deserializer)?;
+        match value {
+            0 => Ok(LampStatus::Free),
+            1 => Ok(LampStatus::Off),
+            2 => Ok(LampStatus::Check),
+            3 => Ok(LampStatus::Running),
+            _ => Err(serde::de::Error::custom("Invalid value for LampStatus")),
+        }
+    }
+}
+
+/// 切换到当前状态的原因
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Reason {
+    // 状态更新
+    StatusModified,
+    // 定时打开
+    TimedOpen,
+    // 定时关闭
+    TimedOff,
+    // 平台打开
+    PlatformOpen,
+    // 平台关闭
+    PlatformOff,
+    // 发生红外报警
+    InfraredAlarmActivated,
+    // 红外报警解除
+    InfraredAlarmDeactivated,
+    // 检测正常
+    DetectionNormal,
+    // 非法灯管
+    IllegalLamp,
+}
+
+impl Serialize for Reason {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_u8(self.as_int())
+    }
+}
+
+impl<'de> Deserialize<'de> for Reason {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = u8::deserialize(deserializer)?;
+        match value {
+            1 => Ok(Reason::StatusModified),
+            2 => Ok(Reason::TimedOpen),
+            3 => Ok(Reason::TimedOff),
+            4 => Ok(Reason::PlatformOpen),
+            5 => Ok(Reason::PlatformOff),
+            6 => Ok(Reason::InfraredAlarmActivated),
+            7 => Ok(Reason::InfraredAlarmDeactivated),
+            8 => Ok(Reason::DetectionNormal),
+            9 => Ok(Reason::IllegalLamp),
+            _ => Err(serde::de::Error::custom("Invalid value for Reason")),
+        }
+    }
+}
+
+impl Reason {
+    pub fn as_int(&self) -> u8 {
+        match self {
+            Reason::StatusModified => 1,
+            Reason::TimedOpen => 2,
+            Reason::TimedOff => 3,
+            Reason::PlatformOpen => 4,
+            Reason::PlatformOff => 5,
+            Reason::InfraredAlarmActivated => 6,
+            Reason::InfraredAlarmDeactivated => 7,
+            Reason::DetectionNormal => 8,
+            Reason::IllegalLamp => 9,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{message_id, LampStatus, MessageId, Reason};
+
+    #[test]
+    fn test_message_id() {
+        assert_eq!(
+            message_id(r#"{"id":123456,"s":1}"#),
+            Some(MessageId::Number(123456))
+        );
+        assert_eq!(
+            message_id(r#"{"id":"482913"}"#).map(|id| id.to_string()),
+            Some("482913".to_string())
+        );
+        assert!(message_id(r#"{"s":1}"#).is_none());
+        assert!(message_id("vI").is_none());
+    }
+
+    #[test]
+    fn test_lamp_status_and_reason() {
+        for value in 0..=3u8 {
+            let status: LampStatus = serde_json::from_str(&value.to_string()).unwrap();
+            assert_eq!(status.as_int(), value);
+        }
+        for value in 1..=9u8 {
+            let reason: Reason = serde_json::from_str(&value.to_string()).unwrap();
+            assert_eq!(serde_json::to_string(&reason).unwrap(), value.to_string());
+        }
+        assert!(serde_json::from_str::<LampStatus>("4").is_err());
+        assert!(serde_json::from_str::<Reason>("0").is_err());
+    }
+}
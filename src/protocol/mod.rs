@@ -0,0 +1,7 @@
+// 紫外线灯 MQTT 协议，每个主题方向对应一个 payload 类型：
+// oc/s `Command`、oc/c `CommandAck`、up/c `StatusReport`、nI/s `NetworkQuery`、nI/c `NetworkReport`；
+// 收到消息分发时只解析处理器需要的字段(`Reply`、`StatusChange`)，完整的结构在生成回调内容时解析
+pub mod command;
+pub mod common;
+pub mod network;
+pub mod status;
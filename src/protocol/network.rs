@@ -0,0 +1,64 @@
+use crate::protocol::common::MessageId;
+use serde::{Deserialize, Serialize};
+
+/// `nI/s` 查询设备网络状态，id 为随机数
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NetworkQuery {
+    pub id: MessageId,
+}
+
+/// `nI/c` 设备上报网络状态，收到即视为在线；生成回调内容时要求带时间戳
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NetworkReport {
+    /// 查询请求的随机数
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<MessageId>,
+    /// 0 表示成功
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<i32>,
+    /// 客户端 IP 地址
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ip: Option<String>,
+    /// 信号质量(1-5，最大值为 5)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rssi: Option<i32>,
+    /// 时间戳: YYYY-MM-DD hh:mm:ss
+    pub ts: String,
+}
+
+#[cfg(test)]
+mod test {
+    use super::{NetworkQuery, NetworkReport};
+    use crate::protocol::common::MessageId;
+    use serde_json::Value;
+
+    #[test]
+    fn test_network_query() {
+        let query = NetworkQuery {
+            id: MessageId::Text("482913".to_string()),
+        };
+        let payload = serde_json::to_string(&query).unwrap();
+        assert_eq!(payload, r#"{"id":"482913"}"#);
+        assert_eq!(
+            serde_json::from_str::<NetworkQuery>(&payload).unwrap(),
+            query
+        );
+    }
+
+    #[test]
+    fn test_network_report() {
+        let payload =
+            r#"{"id":"482913","code":0,"ip":"10.0.3.27","rssi":4,"ts":"2024-05-20 10:16:00"}"#;
+        let report: NetworkReport = serde_json::from_str(payload).unwrap();
+        assert_eq!(report.rssi, Some(4));
+        assert_eq!(report.ts, "2024-05-20 10:16:00");
+        let expected: Value = serde_json::from_str(payload).unwrap();
+        assert_eq!(serde_json::to_value(&report).unwrap(), expected);
+
+        // 只依赖时间戳，其他字段缺失时仍可解析
+        let report: NetworkReport =
+            serde_json::from_str(r#"{"ts":"2024-05-20 10:16:00"}"#).unwrap();
+        assert!(report.id.is_none());
+        assert!(serde_json::from_str::<NetworkReport>(r#"{"id":"482913"}"#).is_err());
+    }
+}
@@ -0,0 +1,73 @@
+use crate::protocol::common::{LampStatus, MessageId, Reason};
+use serde::{Deserialize, Serialize};
+
+/// `up/c` 设备上报开关灯状态，状态由指令触发时带回指令中的 id
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StatusReport {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<MessageId>,
+    #[serde(rename = "s")]
+    pub status: LampStatus,
+    /// 紫外线强度，最大为 200
+    #[serde(rename = "u")]
+    pub strength: u8,
+    /// 消毒开启时间，单位分钟
+    #[serde(rename = "d")]
+    pub duration: i32,
+    /// 时间: YYYY-mm-dd HH:mm:ss
+    #[serde(rename = "ts")]
+    pub timestamp: String,
+    #[serde(rename = "c")]
+    pub reason: Reason,
+}
+
+/// `up/c` 中分发事件所需的字段，其他字段缺失时也要创建通知任务和发布事件，
+/// 完整的 `StatusReport` 只在生成回调内容时解析
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct StatusChange {
+    #[serde(rename = "s")]
+    pub status: LampStatus,
+    #[serde(default, rename = "c")]
+    pub reason: Option<Reason>,
+}
+
+#[cfg(test)]
+mod test {
+    use super::{StatusChange, StatusReport};
+    use crate::protocol::common::{LampStatus, MessageId, Reason};
+    use serde_json::Value;
+
+    #[test]
+    fn test_status_report() {
+        let payloads = [
+            r#"{"id":"482913","s":3,"u":200,"d":30,"ts":"2024-05-20 10:15:30","c":4}"#,
+            r#"{"s":1,"u":0,"d":0,"ts":"2024-05-20 10:45:30","c":3}"#,
+            r#"{"s":1,"u":0,"d":0,"ts":"2024-05-20 11:02:08","c":6}"#,
+        ];
+        for payload in payloads {
+            let report: StatusReport = serde_json::from_str(payload).unwrap();
+            let expected: Value = serde_json::from_str(payload).unwrap();
+            assert_eq!(serde_json::to_value(&report).unwrap(), expected);
+        }
+
+        let report: StatusReport = serde_json::from_str(payloads[0]).unwrap();
+        assert_eq!(report.id, Some(MessageId::Text("482913".to_string())));
+        assert_eq!(report.status, LampStatus::Running);
+        assert_eq!(report.strength, 200);
+        assert_eq!(report.reason, Reason::PlatformOpen);
+
+        assert!(serde_json::from_str::<StatusReport>(r#"{"s":1,"c":3}"#).is_err());
+    }
+
+    #[test]
+    fn test_status_change() {
+        // 没有 u、d、ts 的上报仍然可以分发
+        let change: StatusChange = serde_json::from_str(r#"{"s":1,"c":6}"#).unwrap();
+        assert_eq!(change.status, LampStatus::Off);
+        assert_eq!(change.reason, Some(Reason::InfraredAlarmActivated));
+
+        let change: StatusChange = serde_json::from_str(r#"{"s":3}"#).unwrap();
+        assert!(change.reason.is_none());
+        assert!(serde_json::from_str::<StatusChange>(r#"{"c":3}"#).is_err());
+    }
+}
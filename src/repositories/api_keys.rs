@@ -0,0 +1,92 @@
+use crate::utils::mysql::MySql;
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+
+pub struct ApiKeys;
+
+#[derive(FromRow)]
+pub struct ApiKey {
+    pub id: u64,
+    pub name: String,
+    pub key_prefix: String,
+    pub key_hash: String,
+    pub role: String,
+    pub created_by: u64,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+const COLUMNS: &str = "`id`, `name`, `key_prefix`, `key_hash`, `role`, `created_by`, `last_used_at`, `expires_at`, `revoked_at`, `created_at`";
+
+impl ApiKeys {
+    pub async fn create(
+        name: String,
+        key_prefix: String,
+        key_hash: String,
+        role: String,
+        created_by: u64,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<u64, anyhow::Error> {
+        let db = MySql::get_instance().await?;
+        let sql = "INSERT INTO `api_keys` (`name`, `key_prefix`, `key_hash`, `role`, `created_by`, `expires_at`) VALUES (?, ?, ?, ?, ?, ?);";
+        let result = sqlx::query(sql)
+            .bind(name)
+            .bind(key_prefix)
+            .bind(key_hash)
+            .bind(role)
+            .bind(created_by)
+            .bind(expires_at)
+            .execute(&db.pool)
+            .await?;
+        Ok(result.last_insert_id())
+    }
+
+    pub async fn find_all() -> Result<Vec<ApiKey>, anyhow::Error> {
+        let db = MySql::get_instance().await?;
+        let sql = format!("SELECT {} FROM `api_keys` ORDER BY `id` DESC;", COLUMNS);
+        let keys = sqlx::query_as::<_, ApiKey>(&sql)
+            .fetch_all(&db.pool)
+            .await?;
+        Ok(keys)
+    }
+
+    pub async fn find_by_id(id: u64) -> Result<Option<ApiKey>, anyhow::Error> {
+        let db = MySql::get_instance().await?;
+        let sql = format!("SELECT {} FROM `api_keys` WHERE `id` = ? LIMIT 1;", COLUMNS);
+        let key = sqlx::query_as::<_, ApiKey>(&sql)
+            .bind(id)
+            .fetch_optional(&db.pool)
+            .await?;
+        Ok(key)
+    }
+
+    pub async fn find_by_prefix(key_prefix: &str) -> Result<Option<ApiKey>, anyhow::Error> {
+        let db = MySql::get_instance().await?;
+        let sql = format!(
+            "SELECT {} FROM `api_keys` WHERE `key_prefix` = ? LIMIT 1;",
+            COLUMNS
+        );
+        let key = sqlx::query_as::<_, ApiKey>(&sql)
+            .bind(key_prefix)
+            .fetch_optional(&db.pool)
+            .await?;
+        Ok(key)
+    }
+
+    pub async fn update_last_used_at(id: u64) -> Result<(), anyhow::Error> {
+        let db = MySql::get_instance().await?;
+        let sql = "UPDATE `api_keys` SET `last_used_at` = CURRENT_TIMESTAMP WHERE `id` = ?;";
+        sqlx::query(sql).bind(id).execute(&db.pool).await?;
+        Ok(())
+    }
+
+    /// 返回更新的行数，已经吊销时为 0
+    pub async fn revoke(id: u64) -> Result<u64, anyhow::Error> {
+        let db = MySql::get_instance().await?;
+        let sql = "UPDATE `api_keys` SET `revoked_at` = CURRENT_TIMESTAMP WHERE `id` = ? AND `revoked_at` IS NULL;";
+        let result = sqlx::query(sql).bind(id).execute(&db.pool).await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,114 @@
+use crate::utils::mysql::MySql;
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+
+pub struct IdempotencyKeys;
+
+#[derive(FromRow)]
+pub struct IdempotencyKey {
+    pub id: u64,
+    pub principal: String,
+    pub path: String,
+    pub idempotency_key: String,
+    pub fingerprint: String,
+    pub status_code: Option<u16>,
+    pub response: Option<String>,
+    pub locked_until: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+const COLUMNS: &str = "`id`, `principal`, `path`, `idempotency_key`, `fingerprint`, `status_code`, `response`, `locked_until`, `expires_at`, `created_at`";
+
+impl IdempotencyKeys {
+    /// 插入处理中的记录，key 已存在时返回 `None`
+    pub async fn try_create(
+        principal: &str,
+        path: &str,
+        idempotency_key: &str,
+        fingerprint: &str,
+        locked_until: DateTime<Utc>,
+        expires_at: DateTime<Utc>,
+    ) -> Result<Option<u64>, anyhow::Error> {
+        let db = MySql::get_instance().await?;
+        let sql = "INSERT IGNORE INTO `idempotency_keys` (`principal`, `path`, `idempotency_key`, `fingerprint`, `locked_until`, `expires_at`) VALUES (?, ?, ?, ?, ?, ?);";
+        let result = sqlx::query(sql)
+            .bind(principal)
+            .bind(path)
+            .bind(idempotency_key)
+            .bind(fingerprint)
+            .bind(locked_until)
+            .bind(expires_at)
+            .execute(&db.pool)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Ok(None);
+        }
+        Ok(Some(result.last_insert_id()))
+    }
+
+    pub async fn find(
+        principal: &str,
+        path: &str,
+        idempotency_key: &str,
+    ) -> Result<Option<IdempotencyKey>, anyhow::Error> {
+        let db = MySql::get_instance().await?;
+        let sql = format!(
+            "SELECT {} FROM `idempotency_keys` WHERE `principal` = ? AND `path` = ? AND `idempotency_key` = ? LIMIT 1;",
+            COLUMNS
+        );
+        let key = sqlx::query_as::<_, IdempotencyKey>(&sql)
+            .bind(principal)
+            .bind(path)
+            .bind(idempotency_key)
+            .fetch_optional(&db.pool)
+            .await?;
+        Ok(key)
+    }
+
+    /// 续期处理中的记录，返回更新的行数
+    pub async fn extend_lock(id: u64, locked_until: DateTime<Utc>) -> Result<u64, anyhow::Error> {
+        let db = MySql::get_instance().await?;
+        let sql = "UPDATE `idempotency_keys` SET `locked_until` = ? WHERE `id` = ? AND `status_code` IS NULL;";
+        let result = sqlx::query(sql)
+            .bind(locked_until)
+            .bind(id)
+            .execute(&db.pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// 返回更新的行数，记录已被删除时为 0；`expires_at` 只能提前过期时间
+    pub async fn complete(
+        id: u64,
+        status_code: u16,
+        response: &str,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<u64, anyhow::Error> {
+        let db = MySql::get_instance().await?;
+        let sql = "UPDATE `idempotency_keys` SET `status_code` = ?, `response` = ?, `expires_at` = LEAST(`expires_at`, COALESCE(?, `expires_at`)) WHERE `id` = ?;";
+        let result = sqlx::query(sql)
+            .bind(status_code)
+            .bind(response)
+            .bind(expires_at)
+            .bind(id)
+            .execute(&db.pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    pub async fn delete(id: u64) -> Result<(), anyhow::Error> {
+        let db = MySql::get_instance().await?;
+        let sql = "DELETE FROM `idempotency_keys` WHERE `id` = ?;";
+        sqlx::query(sql).bind(id).execute(&db.pool).await?;
+        Ok(())
+    }
+
+    /// 删除已过期的记录，返回删除的行数
+    pub async fn delete_expired() -> Result<u64, anyhow::Error> {
+        let db = MySql::get_instance().await?;
+        let sql = "DELETE FROM `idempotency_keys` WHERE `expires_at` < CURRENT_TIMESTAMP LIMIT 1000;";
+        let result = sqlx::query(sql).execute(&db.pool).await?;
+        Ok(result.rows_affected())
+    }
+}
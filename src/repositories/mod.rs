@@ -1,3 +1,10 @@
+pub mod api_keys;
+pub mod idempotency_keys;
+pub mod users;
+pub mod uv_lamp_device_groups;
+pub mod uv_lamp_devices;
 pub mod uv_lamp_mqtt_message;
 pub mod uv_lamp_mqtt_notify_job;
+pub mod uv_lamp_mqtt_notify_job_attempts;
 pub mod uv_lamp_mqtt_received_messages;
+pub mod uv_lamp_schedules;
@@ -0,0 +1,104 @@
+use crate::utils::mysql::MySql;
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+
+pub struct Users;
+
+#[derive(FromRow)]
+pub struct User {
+    pub id: u64,
+    pub username: String,
+    pub password_hash: String,
+    pub role: String,
+    pub is_disabled: u8,
+    pub last_login_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+const COLUMNS: &str =
+    "`id`, `username`, `password_hash`, `role`, `is_disabled`, `last_login_at`, `created_at`, `updated_at`";
+
+impl Users {
+    pub async fn create(
+        username: String,
+        password_hash: String,
+        role: String,
+    ) -> Result<u64, anyhow::Error> {
+        let db = MySql::get_instance().await?;
+        let sql = "INSERT INTO `users` (`username`, `password_hash`, `role`) VALUES (?, ?, ?);";
+        let result = sqlx::query(sql)
+            .bind(username)
+            .bind(password_hash)
+            .bind(role)
+            .execute(&db.pool)
+            .await?;
+        Ok(result.last_insert_id())
+    }
+
+    pub async fn count() -> Result<i64, anyhow::Error> {
+        let db = MySql::get_instance().await?;
+        let sql = "SELECT COUNT(*) FROM `users` WHERE `deleted_at` IS NULL;";
+        let count = sqlx::query_scalar::<_, i64>(sql)
+            .fetch_one(&db.pool)
+            .await?;
+        Ok(count)
+    }
+
+    pub async fn find_by_username(username: &str) -> Result<Option<User>, anyhow::Error> {
+        let db = MySql::get_instance().await?;
+        let sql = format!(
+            "SELECT {} FROM `users` WHERE `username` = ? AND `deleted_at` IS NULL LIMIT 1;",
+            COLUMNS
+        );
+        let user = sqlx::query_as::<_, User>(&sql)
+            .bind(username)
+            .fetch_optional(&db.pool)
+            .await?;
+        Ok(user)
+    }
+
+    pub async fn find_by_id(id: u64) -> Result<Option<User>, anyhow::Error> {
+        let db = MySql::get_instance().await?;
+        let sql = format!(
+            "SELECT {} FROM `users` WHERE `id` = ? AND `deleted_at` IS NULL LIMIT 1;",
+            COLUMNS
+        );
+        let user = sqlx::query_as::<_, User>(&sql)
+            .bind(id)
+            .fetch_optional(&db.pool)
+            .await?;
+        Ok(user)
+    }
+
+    pub async fn find_all() -> Result<Vec<User>, anyhow::Error> {
+        let db = MySql::get_instance().await?;
+        let sql = format!(
+            "SELECT {} FROM `users` WHERE `deleted_at` IS NULL ORDER BY `id`;",
+            COLUMNS
+        );
+        let users = sqlx::query_as::<_, User>(&sql)
+            .fetch_all(&db.pool)
+            .await?;
+        Ok(users)
+    }
+
+    pub async fn update(id: u64, role: String, is_disabled: bool) -> Result<(), anyhow::Error> {
+        let db = MySql::get_instance().await?;
+        let sql = "UPDATE `users` SET `role` = ?, `is_disabled` = ? WHERE `id` = ? AND `deleted_at` IS NULL;";
+        sqlx::query(sql)
+            .bind(role)
+            .bind(is_disabled as u8)
+            .bind(id)
+            .execute(&db.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn update_last_login_at(id: u64) -> Result<(), anyhow::Error> {
+        let db = MySql::get_instance().await?;
+        let sql = "UPDATE `users` SET `last_login_at` = CURRENT_TIMESTAMP WHERE `id` = ?;";
+        sqlx::query(sql).bind(id).execute(&db.pool).await?;
+        Ok(())
+    }
+}
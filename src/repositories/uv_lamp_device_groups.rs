@@ -0,0 +1,162 @@
+use crate::utils::mysql::MySql;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, QueryBuilder};
+use std::fmt::{Display, Formatter};
+use utoipa::ToSchema;
+
+pub struct UVLampDeviceGroup;
+
+#[derive(FromRow)]
+pub struct Group {
+    pub id: u64,
+    pub parent_id: u64,
+    pub name: String,
+    pub kind: String,
+    pub remark: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum GroupKind {
+    Building,
+    Floor,
+    Ward,
+    Room,
+    Zone,
+}
+
+impl Display for GroupKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GroupKind::Building => write!(f, "BUILDING"),
+            GroupKind::Floor => write!(f, "FLOOR"),
+            GroupKind::Ward => write!(f, "WARD"),
+            GroupKind::Room => write!(f, "ROOM"),
+            GroupKind::Zone => write!(f, "ZONE"),
+        }
+    }
+}
+
+impl UVLampDeviceGroup {
+    pub async fn create(
+        parent_id: u64,
+        name: String,
+        kind: GroupKind,
+        remark: String,
+    ) -> Result<u64, anyhow::Error> {
+        let db = MySql::get_instance().await?;
+        let sql = "INSERT INTO `uv_lamp_device_groups` (`parent_id`, `name`, `kind`, `remark`) VALUES (?, ?, ?, ?);";
+        let result = sqlx::query(sql)
+            .bind(parent_id)
+            .bind(name)
+            .bind(kind.to_string())
+            .bind(remark)
+            .execute(&db.pool)
+            .await?;
+        Ok(result.last_insert_id())
+    }
+
+    pub async fn find_all() -> Result<Vec<Group>, anyhow::Error> {
+        let db = MySql::get_instance().await?;
+        let sql = "SELECT `id`, `parent_id`, `name`, `kind`, `remark`, `created_at`, `updated_at` FROM `uv_lamp_device_groups` WHERE `deleted_at` IS NULL ORDER BY `id`;";
+        let groups = sqlx::query_as::<_, Group>(sql)
+            .fetch_all(&db.pool)
+            .await?;
+        Ok(groups)
+    }
+
+    pub async fn find_by_id(id: u64) -> Result<Option<Group>, anyhow::Error> {
+        let db = MySql::get_instance().await?;
+        let sql = "SELECT `id`, `parent_id`, `name`, `kind`, `remark`, `created_at`, `updated_at` FROM `uv_lamp_device_groups` WHERE `id` = ? AND `deleted_at` IS NULL LIMIT 1;";
+        let group = sqlx::query_as::<_, Group>(sql)
+            .bind(id)
+            .fetch_optional(&db.pool)
+            .await?;
+        Ok(group)
+    }
+
+    pub async fn update(
+        id: u64,
+        parent_id: u64,
+        name: String,
+        kind: GroupKind,
+        remark: String,
+    ) -> Result<(), anyhow::Error> {
+        let db = MySql::get_instance().await?;
+        let sql = "UPDATE `uv_lamp_device_groups` SET `parent_id` = ?, `name` = ?, `kind` = ?, `remark` = ? WHERE `id` = ? AND `deleted_at` IS NULL;";
+        sqlx::query(sql)
+            .bind(parent_id)
+            .bind(name)
+            .bind(kind.to_string())
+            .bind(remark)
+            .bind(id)
+            .execute(&db.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// 软删除分组，并清空分组成员
+    pub async fn delete(id: u64) -> Result<(), anyhow::Error> {
+        let db = MySql::get_instance().await?;
+        let mut tx = db.pool.begin().await?;
+
+        let sql = "UPDATE `uv_lamp_device_groups` SET `deleted_at` = CURRENT_TIMESTAMP WHERE `id` = ? AND `deleted_at` IS NULL;";
+        sqlx::query(sql).bind(id).execute(&mut *tx).await?;
+
+        let sql = "DELETE FROM `uv_lamp_device_group_members` WHERE `group_id` = ?;";
+        sqlx::query(sql).bind(id).execute(&mut *tx).await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    pub async fn add_members(group_id: u64, device_numbers: &[String]) -> Result<u64, anyhow::Error> {
+        if device_numbers.is_empty() {
+            return Ok(0);
+        }
+        let db = MySql::get_instance().await?;
+        let mut builder = QueryBuilder::new(
+            "INSERT IGNORE INTO `uv_lamp_device_group_members` (`group_id`, `device_number`) ",
+        );
+        builder.push_values(device_numbers, |mut row, device_number| {
+            row.push_bind(group_id).push_bind(device_number.clone());
+        });
+        let result = builder.build().execute(&db.pool).await?;
+        Ok(result.rows_affected())
+    }
+
+    pub async fn remove_member(group_id: u64, device_number: &str) -> Result<u64, anyhow::Error> {
+        let db = MySql::get_instance().await?;
+        let sql = "DELETE FROM `uv_lamp_device_group_members` WHERE `group_id` = ? AND `device_number` = ?;";
+        let result = sqlx::query(sql)
+            .bind(group_id)
+            .bind(device_number)
+            .execute(&db.pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// 查询分组下在用设备的编号，已停用的设备不会返回
+    pub async fn find_device_numbers(group_ids: &[u64]) -> Result<Vec<String>, anyhow::Error> {
+        if group_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let db = MySql::get_instance().await?;
+        let mut builder = QueryBuilder::new(
+            "SELECT DISTINCT `m`.`device_number` FROM `uv_lamp_device_group_members` `m` INNER JOIN `uv_lamp_devices` `d` ON `d`.`device_number` = `m`.`device_number` WHERE `d`.`is_decommissioned` = 0 AND `m`.`group_id` IN (",
+        );
+        let mut separated = builder.separated(", ");
+        for group_id in group_ids {
+            separated.push_bind(*group_id);
+        }
+        builder.push(") ORDER BY `m`.`device_number`");
+        let device_numbers = builder
+            .build_query_scalar::<String>()
+            .fetch_all(&db.pool)
+            .await?;
+        Ok(device_numbers)
+    }
+}
@@ -0,0 +1,124 @@
+use crate::utils::mysql::MySql;
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+
+pub struct UVLampDevice;
+
+#[derive(FromRow)]
+pub struct Device {
+    pub id: u64,
+    pub device_number: String,
+    pub name: String,
+    pub remark: String,
+    /// 为空时使用默认产品
+    pub product_key: String,
+    pub is_decommissioned: u8,
+    pub decommissioned_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl UVLampDevice {
+    pub async fn create(
+        device_number: String,
+        name: String,
+        remark: String,
+        product_key: String,
+    ) -> Result<u64, anyhow::Error> {
+        let db = MySql::get_instance().await?;
+        let sql = "INSERT INTO `uv_lamp_devices` (`device_number`, `name`, `remark`, `product_key`) VALUES (?, ?, ?, ?);";
+        let result = sqlx::query(sql)
+            .bind(device_number)
+            .bind(name)
+            .bind(remark)
+            .bind(product_key)
+            .execute(&db.pool)
+            .await?;
+        Ok(result.last_insert_id())
+    }
+
+    pub async fn find_all(include_decommissioned: bool) -> Result<Vec<Device>, anyhow::Error> {
+        let db = MySql::get_instance().await?;
+        let sql = if include_decommissioned {
+            "SELECT * FROM `uv_lamp_devices` ORDER BY `id`;"
+        } else {
+            "SELECT * FROM `uv_lamp_devices` WHERE `is_decommissioned` = 0 ORDER BY `id`;"
+        };
+        let devices = sqlx::query_as::<_, Device>(sql)
+            .fetch_all(&db.pool)
+            .await?;
+        Ok(devices)
+    }
+
+    pub async fn find_by_device_number(device_number: &str) -> Result<Option<Device>, anyhow::Error> {
+        let db = MySql::get_instance().await?;
+        let sql = "SELECT * FROM `uv_lamp_devices` WHERE `device_number` = ? LIMIT 1;";
+        let device = sqlx::query_as::<_, Device>(sql)
+            .bind(device_number)
+            .fetch_optional(&db.pool)
+            .await?;
+        Ok(device)
+    }
+
+    /// 在用设备的编号，按注册顺序排列，供定时任务轮询使用
+    pub async fn find_active_device_numbers() -> Result<Vec<String>, anyhow::Error> {
+        let db = MySql::get_instance().await?;
+        let sql = "SELECT `device_number` FROM `uv_lamp_devices` WHERE `is_decommissioned` = 0 ORDER BY `id`;";
+        let device_numbers = sqlx::query_scalar::<_, String>(sql)
+            .fetch_all(&db.pool)
+            .await?;
+        Ok(device_numbers)
+    }
+
+    pub async fn update(
+        device_number: &str,
+        name: String,
+        remark: String,
+        product_key: String,
+    ) -> Result<(), anyhow::Error> {
+        let db = MySql::get_instance().await?;
+        let sql = "UPDATE `uv_lamp_devices` SET `name` = ?, `remark` = ?, `product_key` = ? WHERE `device_number` = ?;";
+        sqlx::query(sql)
+            .bind(name)
+            .bind(remark)
+            .bind(product_key)
+            .bind(device_number)
+            .execute(&db.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn recommission(
+        device_number: &str,
+        name: String,
+        remark: String,
+        product_key: String,
+    ) -> Result<(), anyhow::Error> {
+        let db = MySql::get_instance().await?;
+        let sql = "UPDATE `uv_lamp_devices` SET `name` = ?, `remark` = ?, `product_key` = ?, `is_decommissioned` = 0, `decommissioned_at` = NULL WHERE `device_number` = ?;";
+        sqlx::query(sql)
+            .bind(name)
+            .bind(remark)
+            .bind(product_key)
+            .bind(device_number)
+            .execute(&db.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn decommission(device_number: &str) -> Result<(), anyhow::Error> {
+        let db = MySql::get_instance().await?;
+        let sql = "UPDATE `uv_lamp_devices` SET `is_decommissioned` = 1, `decommissioned_at` = CURRENT_TIMESTAMP WHERE `device_number` = ? AND `is_decommissioned` = 0;";
+        let result = sqlx::query(sql)
+            .bind(device_number)
+            .execute(&db.pool)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(anyhow::anyhow!(
+                "Device {} is already decommissioned",
+                device_number
+            ));
+        }
+        Ok(())
+    }
+}
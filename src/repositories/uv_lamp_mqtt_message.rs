@@ -1,21 +1,147 @@
+use crate::params::requests::common::Pagination;
 use crate::utils::mysql::MySql;
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, MySql as MySqlDriver, QueryBuilder};
 
 pub struct UVLampMqttMessage;
 
+#[derive(FromRow)]
+pub struct Message {
+    pub id: u64,
+    pub message_id: String,
+    pub device_number: String,
+    pub payload: String,
+    pub request_id: Option<String>,
+    pub is_acked: u8,
+    pub acked_at: Option<DateTime<Utc>>,
+    pub ack_latency_ms: Option<u32>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Default)]
+pub struct MessageFilter {
+    pub device_number: Option<String>,
+    pub device_numbers: Option<Vec<String>>,
+    pub message_id: Option<String>,
+    pub is_acked: Option<bool>,
+}
+
+impl MessageFilter {
+    fn push_conditions(&self, builder: &mut QueryBuilder<'_, MySqlDriver>) {
+        builder.push(" WHERE `is_deleted` = 0");
+        if let Some(device_number) = &self.device_number {
+            builder.push(" AND `device_number` = ").push_bind(device_number.clone());
+        }
+        if let Some(device_numbers) = &self.device_numbers {
+            if device_numbers.is_empty() {
+                builder.push(" AND 1 = 0");
+            } else {
+                builder.push(" AND `device_number` IN (");
+                let mut separated = builder.separated(", ");
+                for device_number in device_numbers {
+                    separated.push_bind(device_number.clone());
+                }
+                builder.push(")");
+            }
+        }
+        if let Some(message_id) = &self.message_id {
+            builder.push(" AND `message_id` = ").push_bind(message_id.clone());
+        }
+        if let Some(is_acked) = self.is_acked {
+            builder.push(" AND `is_acked` = ").push_bind(is_acked as u8);
+        }
+    }
+}
+
 impl UVLampMqttMessage {
     pub async fn create(
         message_id: String,
         device_number: String,
         payload: String,
+        request_id: Option<String>,
     ) -> Result<(), anyhow::Error> {
         let db = MySql::get_instance().await?;
-        let sql = "INSERT INTO `uv_lamp_mqtt_messages` (`message_id`, `device_number`, `payload`) value (?, ?, ?);" ;
+        let sql = "INSERT INTO `uv_lamp_mqtt_messages` (`message_id`, `device_number`, `payload`, `request_id`) value (?, ?, ?, ?);" ;
         sqlx::query(sql)
             .bind(message_id)
             .bind(device_number)
             .bind(payload)
+            .bind(request_id)
             .execute(&db.pool)
             .await?;
         Ok(())
     }
+
+    /// 将设备最近一条未确认的同 ID 指令标记为已确认，返回确认耗时(毫秒)，没有匹配的指令时返回 `None`
+    pub async fn mark_acked(
+        message_id: &str,
+        device_number: &str,
+    ) -> Result<Option<u32>, anyhow::Error> {
+        let db = MySql::get_instance().await?;
+        // 同一设备的 message_id 可能重复，锁定要确认的那一行，再按主键更新和读取耗时
+        let mut tx = db.pool.begin().await?;
+        let sql = "SELECT `id` FROM `uv_lamp_mqtt_messages` WHERE `device_number` = ? AND `message_id` = ? AND `is_acked` = 0 AND `is_deleted` = 0 ORDER BY `id` DESC LIMIT 1 FOR UPDATE;";
+        let id = sqlx::query_scalar::<_, u64>(sql)
+            .bind(device_number)
+            .bind(message_id)
+            .fetch_optional(&mut *tx)
+            .await?;
+        let id = match id {
+            Some(id) => id,
+            None => return Ok(None),
+        };
+
+        let sql = "UPDATE `uv_lamp_mqtt_messages` SET `is_acked` = 1, `acked_at` = CURRENT_TIMESTAMP(3), `ack_latency_ms` = TIMESTAMPDIFF(MICROSECOND, `created_at`, CURRENT_TIMESTAMP(3)) DIV 1000 WHERE `id` = ?;";
+        sqlx::query(sql).bind(id).execute(&mut *tx).await?;
+
+        let sql = "SELECT `ack_latency_ms` FROM `uv_lamp_mqtt_messages` WHERE `id` = ?;";
+        let latency = sqlx::query_scalar::<_, Option<u32>>(sql)
+            .bind(id)
+            .fetch_one(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(latency)
+    }
+
+    /// 设备最近一条同 ID 指令的请求 ID，设备主动上报或指令不是由接口下发时返回 `None`
+    pub async fn find_request_id(
+        message_id: &str,
+        device_number: &str,
+    ) -> Result<Option<String>, anyhow::Error> {
+        let db = MySql::get_instance().await?;
+        let sql = "SELECT `request_id` FROM `uv_lamp_mqtt_messages` WHERE `device_number` = ? AND `message_id` = ? AND `is_deleted` = 0 ORDER BY `id` DESC LIMIT 1;";
+        let request_id = sqlx::query_scalar::<_, Option<String>>(sql)
+            .bind(device_number)
+            .bind(message_id)
+            .fetch_optional(&db.pool)
+            .await?;
+        Ok(request_id.flatten())
+    }
+
+    pub async fn find_page(
+        filter: &MessageFilter,
+        pagination: Pagination,
+    ) -> Result<(Vec<Message>, i64), anyhow::Error> {
+        let db = MySql::get_instance().await?;
+
+        let mut builder = QueryBuilder::new("SELECT COUNT(*) FROM `uv_lamp_mqtt_messages`");
+        filter.push_conditions(&mut builder);
+        let total: i64 = builder.build_query_scalar().fetch_one(&db.pool).await?;
+
+        let mut builder = QueryBuilder::new(
+            "SELECT `id`, `message_id`, `device_number`, `payload`, `request_id`, `is_acked`, `acked_at`, `ack_latency_ms`, `created_at` FROM `uv_lamp_mqtt_messages`",
+        );
+        filter.push_conditions(&mut builder);
+        builder
+            .push(" ORDER BY `id` DESC LIMIT ")
+            .push_bind(pagination.limit())
+            .push(" OFFSET ")
+            .push_bind(pagination.offset());
+        let messages = builder
+            .build_query_as::<Message>()
+            .fetch_all(&db.pool)
+            .await?;
+
+        Ok((messages, total))
+    }
 }
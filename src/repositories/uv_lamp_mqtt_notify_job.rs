@@ -1,7 +1,10 @@
+use crate::params::requests::common::Pagination;
 use crate::utils::mysql::MySql;
 use chrono::{DateTime, Utc};
-use sqlx::FromRow;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, MySql as MySqlDriver, QueryBuilder};
 use tracing::debug;
+use utoipa::ToSchema;
 
 pub struct UVLampMqttNotifyJob;
 
@@ -13,7 +16,10 @@ pub struct Job {
     pub is_completed: u8,
     pub retry_count: u8,
     pub next_retry_time: u64,
-    pub deleted_at: Option<u64>,
+    #[sqlx(rename = "type")]
+    pub job_type: String,
+    pub request_id: Option<String>,
+    pub deleted_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -35,18 +41,78 @@ impl IsCompleted {
     }
 }
 
+/// 任务对外展示的状态，已取消的任务通过 `deleted_at` 标记
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum JobState {
+    Incomplete,
+    Complete,
+    Failed,
+    Cancelled,
+}
+
+impl Job {
+    pub fn state(&self) -> JobState {
+        if self.deleted_at.is_some() {
+            return JobState::Cancelled;
+        }
+        match self.is_completed {
+            1 => JobState::Complete,
+            2 => JobState::Failed,
+            _ => JobState::Incomplete,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct JobFilter {
+    pub state: Option<JobState>,
+    pub job_type: Option<String>,
+    pub device_number: Option<String>,
+}
+
+impl JobFilter {
+    fn push_conditions(&self, builder: &mut QueryBuilder<'_, MySqlDriver>) {
+        builder.push(" WHERE 1 = 1");
+        match self.state {
+            Some(JobState::Cancelled) => {
+                builder.push(" AND `deleted_at` IS NOT NULL");
+            }
+            Some(state) => {
+                let is_completed = match state {
+                    JobState::Complete => IsCompleted::Complete,
+                    JobState::Failed => IsCompleted::Failed,
+                    _ => IsCompleted::Incomplete,
+                };
+                builder
+                    .push(" AND `deleted_at` IS NULL AND `is_completed` = ")
+                    .push_bind(is_completed.as_i32());
+            }
+            None => {}
+        }
+        if let Some(job_type) = &self.job_type {
+            builder.push(" AND `type` = ").push_bind(job_type.clone());
+        }
+        if let Some(device_number) = &self.device_number {
+            builder.push(" AND `device_number` = ").push_bind(device_number.clone());
+        }
+    }
+}
+
 impl UVLampMqttNotifyJob {
     pub async fn create(
         device_number: String,
         notify_contents: String,
         job_type: String,
+        request_id: Option<String>,
     ) -> Result<u64, anyhow::Error> {
         let db = MySql::get_instance().await?;
-        let sql = "INSERT INTO `uv_lamp_mqtt_notify_jobs` (`device_number`, `notify_contents`, `type`) value (?, ?, ?)";
+        let sql = "INSERT INTO `uv_lamp_mqtt_notify_jobs` (`device_number`, `notify_contents`, `type`, `request_id`) value (?, ?, ?, ?)";
         let result = sqlx::query(sql)
             .bind(device_number)
             .bind(notify_contents)
             .bind(job_type)
+            .bind(request_id)
             .execute(&db.pool)
             .await?;
         Ok(result.last_insert_id())
@@ -56,7 +122,7 @@ impl UVLampMqttNotifyJob {
         let db = MySql::get_instance().await?;
         let current_time = Utc::now().timestamp() as u64;
 
-        let sql = "SELECT * from `uv_lamp_mqtt_notify_jobs` where `retry_count` <= ? and `is_completed` = ? and `next_retry_time` <= ? and `type` = ? limit 10;";
+        let sql = "SELECT * from `uv_lamp_mqtt_notify_jobs` where `retry_count` <= ? and `is_completed` = ? and `next_retry_time` <= ? and `type` = ? and `deleted_at` is null limit 10;";
 
         let jobs = sqlx::query_as::<_, Job>(sql)
             .bind(max_retry_count)
@@ -128,4 +194,67 @@ impl UVLampMqttNotifyJob {
         }
         Ok(())
     }
+
+    pub async fn find_by_id(id: u64) -> Result<Option<Job>, anyhow::Error> {
+        let db = MySql::get_instance().await?;
+        let sql = "SELECT * FROM `uv_lamp_mqtt_notify_jobs` WHERE `id` = ?;";
+        let job = sqlx::query_as::<_, Job>(sql)
+            .bind(id)
+            .fetch_optional(&db.pool)
+            .await?;
+        Ok(job)
+    }
+
+    pub async fn find_page(
+        filter: &JobFilter,
+        pagination: Pagination,
+    ) -> Result<(Vec<Job>, i64), anyhow::Error> {
+        let db = MySql::get_instance().await?;
+
+        let mut builder = QueryBuilder::new("SELECT COUNT(*) FROM `uv_lamp_mqtt_notify_jobs`");
+        filter.push_conditions(&mut builder);
+        let total: i64 = builder.build_query_scalar().fetch_one(&db.pool).await?;
+
+        let mut builder = QueryBuilder::new("SELECT * FROM `uv_lamp_mqtt_notify_jobs`");
+        filter.push_conditions(&mut builder);
+        builder
+            .push(" ORDER BY `id` DESC LIMIT ")
+            .push_bind(pagination.limit())
+            .push(" OFFSET ")
+            .push_bind(pagination.offset());
+        let jobs = builder.build_query_as::<Job>().fetch_all(&db.pool).await?;
+
+        Ok((jobs, total))
+    }
+
+    /// 将失败的任务重置为未完成，并清零重试次数，使其在下一轮立即重新发送
+    pub async fn reset_failed(id: u64) -> Result<(), anyhow::Error> {
+        let db = MySql::get_instance().await?;
+        let sql = "UPDATE `uv_lamp_mqtt_notify_jobs` SET `is_completed` = ?, `retry_count` = 0, `next_retry_time` = 0 WHERE `id` = ? AND `is_completed` = ? AND `deleted_at` IS NULL;";
+        let result = sqlx::query(sql)
+            .bind(IsCompleted::Incomplete.as_i32())
+            .bind(id)
+            .bind(IsCompleted::Failed.as_i32())
+            .execute(&db.pool)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(anyhow::anyhow!("Job {} is not in failed state", id));
+        }
+        Ok(())
+    }
+
+    /// 取消未完成的任务，取消后不再发送
+    pub async fn cancel(id: u64) -> Result<(), anyhow::Error> {
+        let db = MySql::get_instance().await?;
+        let sql = "UPDATE `uv_lamp_mqtt_notify_jobs` SET `deleted_at` = CURRENT_TIMESTAMP WHERE `id` = ? AND `is_completed` = ? AND `deleted_at` IS NULL;";
+        let result = sqlx::query(sql)
+            .bind(id)
+            .bind(IsCompleted::Incomplete.as_i32())
+            .execute(&db.pool)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(anyhow::anyhow!("Job {} is not pending", id));
+        }
+        Ok(())
+    }
 }
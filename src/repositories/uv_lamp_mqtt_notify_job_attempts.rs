@@ -0,0 +1,48 @@
+use crate::utils::mysql::MySql;
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+
+pub struct UVLampMqttNotifyJobAttempt;
+
+#[derive(FromRow)]
+pub struct Attempt {
+    pub id: u64,
+    pub job_id: u64,
+    pub attempt: u16,
+    pub is_success: u8,
+    pub status_code: Option<u16>,
+    pub error: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl UVLampMqttNotifyJobAttempt {
+    /// 第几次发送按已有记录递增，手动重试后继续编号
+    pub async fn create(
+        job_id: u64,
+        is_success: bool,
+        status_code: Option<u16>,
+        error: String,
+    ) -> Result<u64, anyhow::Error> {
+        let db = MySql::get_instance().await?;
+        let sql = "INSERT INTO `uv_lamp_mqtt_notify_job_attempts` (`job_id`, `attempt`, `is_success`, `status_code`, `error`) SELECT ?, COALESCE(MAX(`attempt`), 0) + 1, ?, ?, ? FROM `uv_lamp_mqtt_notify_job_attempts` WHERE `job_id` = ?;";
+        let result = sqlx::query(sql)
+            .bind(job_id)
+            .bind(is_success as u8)
+            .bind(status_code)
+            .bind(error)
+            .bind(job_id)
+            .execute(&db.pool)
+            .await?;
+        Ok(result.last_insert_id())
+    }
+
+    pub async fn find_by_job_id(job_id: u64) -> Result<Vec<Attempt>, anyhow::Error> {
+        let db = MySql::get_instance().await?;
+        let sql = "SELECT * FROM `uv_lamp_mqtt_notify_job_attempts` WHERE `job_id` = ? ORDER BY `id`;";
+        let attempts = sqlx::query_as::<_, Attempt>(sql)
+            .bind(job_id)
+            .fetch_all(&db.pool)
+            .await?;
+        Ok(attempts)
+    }
+}
@@ -1,17 +1,88 @@
+use crate::params::requests::common::Pagination;
 use crate::utils::mysql::MySql;
+use crate::utils::topic::TopicKind;
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, MySql as MySqlDriver, QueryBuilder};
 
 pub struct UVLampMqttReceivedMessages;
 
+#[derive(FromRow)]
+pub struct ReceivedMessage {
+    pub id: u64,
+    pub topic: String,
+    // 通道的类型段，如 `oc`、`up`、`nI`，无法识别时为空
+    pub kind: String,
+    pub device_number: String,
+    pub payload: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Default)]
+pub struct ReceivedMessageFilter {
+    pub device_number: Option<String>,
+    pub device_numbers: Option<Vec<String>>,
+    pub kind: Option<TopicKind>,
+    pub start_time: Option<DateTime<Utc>>,
+    pub end_time: Option<DateTime<Utc>>,
+    // 消息内容包含的文本
+    pub keyword: Option<String>,
+}
+
+impl ReceivedMessageFilter {
+    fn push_conditions(&self, builder: &mut QueryBuilder<'_, MySqlDriver>) {
+        builder.push(" WHERE `deleted_at` IS NULL");
+        if let Some(device_number) = &self.device_number {
+            builder.push(" AND `device_number` = ").push_bind(device_number.clone());
+        }
+        if let Some(device_numbers) = &self.device_numbers {
+            if device_numbers.is_empty() {
+                builder.push(" AND 1 = 0");
+            } else {
+                builder.push(" AND `device_number` IN (");
+                let mut separated = builder.separated(", ");
+                for device_number in device_numbers {
+                    separated.push_bind(device_number.clone());
+                }
+                builder.push(")");
+            }
+        }
+        if let Some(kind) = self.kind {
+            builder.push(" AND `kind` = ").push_bind(kind.as_segment());
+        }
+        if let Some(start_time) = self.start_time {
+            builder.push(" AND `created_at` >= ").push_bind(start_time);
+        }
+        if let Some(end_time) = self.end_time {
+            builder.push(" AND `created_at` < ").push_bind(end_time);
+        }
+        if let Some(keyword) = &self.keyword {
+            builder
+                .push(" AND `payload` LIKE ")
+                .push_bind(format!("%{}%", escape_like(keyword)));
+        }
+    }
+}
+
+/// 转义 LIKE 中的通配符，使关键字按字面匹配
+fn escape_like(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
 impl UVLampMqttReceivedMessages {
     pub async fn create(
-        topic: &String,
+        topic: &str,
+        kind: Option<TopicKind>,
         device_number: String,
-        payload: &String,
+        payload: &str,
     ) -> Result<u64, anyhow::Error> {
         let db = MySql::get_instance().await?;
-        let sql = "INSERT INTO `uv_lamp_mqtt_received_messages` (`topic`, `device_number`, `payload`) VALUES (?, ?, ?);";
+        let sql = "INSERT INTO `uv_lamp_mqtt_received_messages` (`topic`, `kind`, `device_number`, `payload`) VALUES (?, ?, ?, ?);";
         let result = sqlx::query(sql)
             .bind(topic)
+            .bind(kind.map_or("", |kind| kind.as_segment()))
             .bind(device_number)
             .bind(payload)
             .execute(&db.pool)
@@ -19,4 +90,43 @@ impl UVLampMqttReceivedMessages {
         let last_insert_id = result.last_insert_id();
         Ok(last_insert_id)
     }
+
+    pub async fn find_page(
+        filter: &ReceivedMessageFilter,
+        pagination: Pagination,
+    ) -> Result<(Vec<ReceivedMessage>, i64), anyhow::Error> {
+        let db = MySql::get_instance().await?;
+
+        let mut builder =
+            QueryBuilder::new("SELECT COUNT(*) FROM `uv_lamp_mqtt_received_messages`");
+        filter.push_conditions(&mut builder);
+        let total: i64 = builder.build_query_scalar().fetch_one(&db.pool).await?;
+
+        let mut builder = QueryBuilder::new(
+            "SELECT `id`, `topic`, `kind`, `device_number`, `payload`, `created_at` FROM `uv_lamp_mqtt_received_messages`",
+        );
+        filter.push_conditions(&mut builder);
+        builder
+            .push(" ORDER BY `id` DESC LIMIT ")
+            .push_bind(pagination.limit())
+            .push(" OFFSET ")
+            .push_bind(pagination.offset());
+        let messages = builder
+            .build_query_as::<ReceivedMessage>()
+            .fetch_all(&db.pool)
+            .await?;
+
+        Ok((messages, total))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::escape_like;
+
+    #[test]
+    fn test_escape_like() {
+        assert_eq!(escape_like("\"s\":1"), "\"s\":1");
+        assert_eq!(escape_like("50%_a\\b"), "50\\%\\_a\\\\b");
+    }
 }
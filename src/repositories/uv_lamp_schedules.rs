@@ -0,0 +1,142 @@
+use crate::utils::mysql::MySql;
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+
+pub struct UVLampSchedule;
+
+#[derive(FromRow, Clone)]
+pub struct Schedule {
+    pub id: u64,
+    pub name: String,
+    pub cron_expression: String,
+    pub weekdays: String,
+    pub time_of_day: String,
+    pub device_number: String,
+    pub group_id: u64,
+    pub status: u8,
+    pub duration: i32,
+    pub timezone: String,
+    pub is_enabled: u8,
+    pub last_run_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// 新建或更新消毒计划时写入的字段
+pub struct ScheduleFields {
+    pub name: String,
+    pub cron_expression: String,
+    pub weekdays: String,
+    pub time_of_day: String,
+    pub device_number: String,
+    pub group_id: u64,
+    pub status: bool,
+    pub duration: i32,
+    pub timezone: String,
+    pub is_enabled: bool,
+}
+
+const COLUMNS: &str = "`id`, `name`, `cron_expression`, `weekdays`, `time_of_day`, `device_number`, `group_id`, `status`, `duration`, `timezone`, `is_enabled`, `last_run_at`, `created_at`, `updated_at`";
+
+impl UVLampSchedule {
+    pub async fn create(fields: ScheduleFields) -> Result<u64, anyhow::Error> {
+        let db = MySql::get_instance().await?;
+        let sql = "INSERT INTO `uv_lamp_schedules` (`name`, `cron_expression`, `weekdays`, `time_of_day`, `device_number`, `group_id`, `status`, `duration`, `timezone`, `is_enabled`) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?);";
+        let result = sqlx::query(sql)
+            .bind(fields.name)
+            .bind(fields.cron_expression)
+            .bind(fields.weekdays)
+            .bind(fields.time_of_day)
+            .bind(fields.device_number)
+            .bind(fields.group_id)
+            .bind(fields.status as u8)
+            .bind(fields.duration)
+            .bind(fields.timezone)
+            .bind(fields.is_enabled as u8)
+            .execute(&db.pool)
+            .await?;
+        Ok(result.last_insert_id())
+    }
+
+    pub async fn find_all() -> Result<Vec<Schedule>, anyhow::Error> {
+        let db = MySql::get_instance().await?;
+        let sql = format!(
+            "SELECT {} FROM `uv_lamp_schedules` WHERE `deleted_at` IS NULL ORDER BY `id`;",
+            COLUMNS
+        );
+        let schedules = sqlx::query_as::<_, Schedule>(&sql)
+            .fetch_all(&db.pool)
+            .await?;
+        Ok(schedules)
+    }
+
+    pub async fn find_enabled() -> Result<Vec<Schedule>, anyhow::Error> {
+        let db = MySql::get_instance().await?;
+        let sql = format!(
+            "SELECT {} FROM `uv_lamp_schedules` WHERE `deleted_at` IS NULL AND `is_enabled` = 1 ORDER BY `id`;",
+            COLUMNS
+        );
+        let schedules = sqlx::query_as::<_, Schedule>(&sql)
+            .fetch_all(&db.pool)
+            .await?;
+        Ok(schedules)
+    }
+
+    pub async fn find_by_id(id: u64) -> Result<Option<Schedule>, anyhow::Error> {
+        let db = MySql::get_instance().await?;
+        let sql = format!(
+            "SELECT {} FROM `uv_lamp_schedules` WHERE `id` = ? AND `deleted_at` IS NULL LIMIT 1;",
+            COLUMNS
+        );
+        let schedule = sqlx::query_as::<_, Schedule>(&sql)
+            .bind(id)
+            .fetch_optional(&db.pool)
+            .await?;
+        Ok(schedule)
+    }
+
+    /// 以该分组为目标的计划 ID，含已停用的计划
+    pub async fn find_ids_by_group_id(group_id: u64) -> Result<Vec<u64>, anyhow::Error> {
+        let db = MySql::get_instance().await?;
+        let sql = "SELECT `id` FROM `uv_lamp_schedules` WHERE `group_id` = ? AND `deleted_at` IS NULL ORDER BY `id`;";
+        let ids = sqlx::query_scalar::<_, u64>(sql)
+            .bind(group_id)
+            .fetch_all(&db.pool)
+            .await?;
+        Ok(ids)
+    }
+
+    pub async fn update(id: u64, fields: ScheduleFields) -> Result<(), anyhow::Error> {
+        let db = MySql::get_instance().await?;
+        let sql = "UPDATE `uv_lamp_schedules` SET `name` = ?, `cron_expression` = ?, `weekdays` = ?, `time_of_day` = ?, `device_number` = ?, `group_id` = ?, `status` = ?, `duration` = ?, `timezone` = ?, `is_enabled` = ? WHERE `id` = ? AND `deleted_at` IS NULL;";
+        sqlx::query(sql)
+            .bind(fields.name)
+            .bind(fields.cron_expression)
+            .bind(fields.weekdays)
+            .bind(fields.time_of_day)
+            .bind(fields.device_number)
+            .bind(fields.group_id)
+            .bind(fields.status as u8)
+            .bind(fields.duration)
+            .bind(fields.timezone)
+            .bind(fields.is_enabled as u8)
+            .bind(id)
+            .execute(&db.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn delete(id: u64) -> Result<(), anyhow::Error> {
+        let db = MySql::get_instance().await?;
+        let sql = "UPDATE `uv_lamp_schedules` SET `deleted_at` = CURRENT_TIMESTAMP WHERE `id` = ? AND `deleted_at` IS NULL;";
+        sqlx::query(sql).bind(id).execute(&db.pool).await?;
+        Ok(())
+    }
+
+    pub async fn update_last_run_at(id: u64) -> Result<(), anyhow::Error> {
+        let db = MySql::get_instance().await?;
+        let sql = "UPDATE `uv_lamp_schedules` SET `last_run_at` = CURRENT_TIMESTAMP WHERE `id` = ?;";
+        sqlx::query(sql).bind(id).execute(&db.pool).await?;
+        Ok(())
+    }
+}
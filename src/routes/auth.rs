@@ -0,0 +1,19 @@
+use crate::handles::api_key::{issue_api_key, list_api_keys, revoke_api_key};
+use crate::handles::auth::{create_user, list_users, login, update_user};
+use crate::middlewares::role::require_role;
+use crate::services::auth::auth_service::Role;
+use axum::middleware;
+use axum::routing::{delete, post, put, Router};
+
+pub fn register_auth_routes() -> Router {
+    Router::new().route("/auth/login", post(login))
+}
+
+pub fn register_auth_admin_routes() -> Router {
+    Router::new()
+        .route("/auth/api_keys", post(issue_api_key).get(list_api_keys))
+        .route("/auth/api_keys/:id", delete(revoke_api_key))
+        .route("/auth/users", post(create_user).get(list_users))
+        .route("/auth/users/:id", put(update_user))
+        .route_layer(middleware::from_fn_with_state(Role::Admin, require_role))
+}
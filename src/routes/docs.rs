@@ -0,0 +1,115 @@
+use crate::handles::{
+    api_key, auth, health, uv_lamp, uv_lamp_device, uv_lamp_group, uv_lamp_notify_job,
+    uv_lamp_schedule,
+};
+use crate::middlewares::auth::API_KEY_HEADER;
+use axum::Router;
+use utoipa::openapi::security::{ApiKey, ApiKeyValue, HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::{Modify, OpenApi};
+use utoipa_swagger_ui::SwaggerUi;
+
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "Connect X", description = "紫外线灯控制服务接口"),
+    paths(
+        health::healthz,
+        health::readyz,
+        auth::login,
+        auth::create_user,
+        auth::list_users,
+        auth::update_user,
+        api_key::issue_api_key,
+        api_key::list_api_keys,
+        api_key::revoke_api_key,
+        uv_lamp::turn,
+        uv_lamp::batch_turn,
+        uv_lamp::list_commands,
+        uv_lamp::list_messages,
+        uv_lamp::stream_events,
+        uv_lamp::mqtt_status,
+        uv_lamp_device::create_device,
+        uv_lamp_device::list_devices,
+        uv_lamp_device::show_device,
+        uv_lamp_device::update_device,
+        uv_lamp_device::decommission_device,
+        uv_lamp_device::device_status,
+        uv_lamp_device::list_device_status,
+        uv_lamp_group::create_group,
+        uv_lamp_group::list_groups,
+        uv_lamp_group::show_group,
+        uv_lamp_group::update_group,
+        uv_lamp_group::delete_group,
+        uv_lamp_group::add_group_devices,
+        uv_lamp_group::remove_group_device,
+        uv_lamp_schedule::create_schedule,
+        uv_lamp_schedule::list_schedules,
+        uv_lamp_schedule::show_schedule,
+        uv_lamp_schedule::update_schedule,
+        uv_lamp_schedule::delete_schedule,
+        uv_lamp_notify_job::list_notify_jobs,
+        uv_lamp_notify_job::show_notify_job,
+        uv_lamp_notify_job::retry_notify_job,
+        uv_lamp_notify_job::cancel_notify_job,
+    ),
+    modifiers(&SecurityAddon),
+    tags(
+        (name = "health", description = "存活和就绪检查"),
+        (name = "auth", description = "登录、用户和 API Key"),
+        (name = "uv_lamp", description = "开关灯、指令和设备消息"),
+        (name = "device", description = "设备登记和在线状态"),
+        (name = "group", description = "设备分组"),
+        (name = "schedule", description = "消毒计划"),
+        (name = "notify_job", description = "回调通知任务"),
+    )
+)]
+pub struct ApiDoc;
+
+struct SecurityAddon;
+
+impl Modify for SecurityAddon {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "bearer",
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .bearer_format("JWT")
+                    .build(),
+            ),
+        );
+        components.add_security_scheme(
+            "api_key",
+            SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::new(API_KEY_HEADER))),
+        );
+    }
+}
+
+/// `/openapi.json` 返回接口文档，`/docs` 为 Swagger UI 页面，均不需要登录
+pub fn register_docs_routes() -> Router {
+    SwaggerUi::new("/docs")
+        .url("/openapi.json", ApiDoc::openapi())
+        .into()
+}
+
+#[cfg(test)]
+mod test {
+    use super::ApiDoc;
+    use serde_json::Value;
+    use utoipa::OpenApi;
+
+    #[test]
+    fn test_openapi_contains_validation_ranges() {
+        let spec: Value = serde_json::to_value(ApiDoc::openapi()).unwrap();
+        let turn = &spec["components"]["schemas"]["TurnParams"]["properties"];
+        assert_eq!(turn["message_id"]["minimum"], 100_000);
+        assert_eq!(turn["message_id"]["maximum"], 999_999);
+        assert_eq!(turn["device_number"]["minLength"], 12);
+        assert_eq!(turn["device_number"]["maxLength"], 18);
+
+        let paths = spec["paths"].as_object().unwrap();
+        assert!(paths.contains_key("/uv_lamp/turn"));
+        assert!(paths.contains_key("/uv_lamp/devices/{device_number}/status"));
+        assert!(spec["components"]["securitySchemes"]["api_key"].is_object());
+    }
+}
@@ -0,0 +1,8 @@
+use crate::handles::health::{healthz, readyz};
+use axum::routing::{get, Router};
+
+pub fn register_health_routes() -> Router {
+    Router::new()
+        .route("/healthz", get(healthz))
+        .route("/readyz", get(readyz))
+}
@@ -0,0 +1,6 @@
+use crate::handles::metrics::metrics;
+use axum::routing::{get, Router};
+
+pub fn register_metrics_routes() -> Router {
+    Router::new().route("/metrics", get(metrics))
+}
@@ -1 +1,5 @@
+pub mod auth;
+pub mod docs;
+pub mod health;
+pub mod metrics;
 pub mod uv_lamp;
@@ -1,6 +1,88 @@
-use crate::handles::uv_lamp::turn;
-use axum::routing::{post, Router};
+use crate::handles::uv_lamp::{
+    batch_turn, list_commands, list_messages, mqtt_status, stream_events, turn,
+};
+use crate::handles::uv_lamp_device::{
+    create_device, decommission_device, device_status, list_device_status, list_devices,
+    show_device, update_device,
+};
+use crate::handles::uv_lamp_group::{
+    add_group_devices, create_group, delete_group, list_groups, remove_group_device, show_group,
+    update_group,
+};
+use crate::handles::uv_lamp_notify_job::{
+    cancel_notify_job, list_notify_jobs, retry_notify_job, show_notify_job,
+};
+use crate::handles::uv_lamp_schedule::{
+    create_schedule, delete_schedule, list_schedules, show_schedule, update_schedule,
+};
+use crate::middlewares::idempotency::idempotency;
+use crate::middlewares::role::require_role;
+use crate::services::auth::auth_service::Role;
+use axum::middleware;
+use axum::routing::{delete, get, post, put, Router};
 
 pub fn register_uv_lamp_routes() -> Router {
-    Router::new().route("/uv_lamp/turn", post(turn))
+    Router::new()
+        .merge(register_viewer_routes())
+        .merge(register_operator_routes())
+        .merge(register_admin_routes())
+}
+
+/// 只读接口
+fn register_viewer_routes() -> Router {
+    Router::new()
+        .route("/uv_lamp/commands", get(list_commands))
+        .route("/uv_lamp/messages", get(list_messages))
+        .route("/uv_lamp/events", get(stream_events))
+        .route("/uv_lamp/mqtt/status", get(mqtt_status))
+        .route("/uv_lamp/devices", get(list_devices))
+        .route("/uv_lamp/devices/status", get(list_device_status))
+        .route("/uv_lamp/devices/:device_number", get(show_device))
+        .route("/uv_lamp/devices/:device_number/status", get(device_status))
+        .route("/uv_lamp/groups", get(list_groups))
+        .route("/uv_lamp/groups/:id", get(show_group))
+        .route("/uv_lamp/schedules", get(list_schedules))
+        .route("/uv_lamp/schedules/:id", get(show_schedule))
+        .route_layer(middleware::from_fn_with_state(Role::Viewer, require_role))
+}
+
+/// 开关灯
+fn register_operator_routes() -> Router {
+    Router::new()
+        .route(
+            "/uv_lamp/turn",
+            post(turn).layer(middleware::from_fn(idempotency)),
+        )
+        .route(
+            "/uv_lamp/batch_turn",
+            post(batch_turn).layer(middleware::from_fn(idempotency)),
+        )
+        .route_layer(middleware::from_fn_with_state(Role::Operator, require_role))
+}
+
+/// 设备、分组、消毒计划和通知任务的管理
+fn register_admin_routes() -> Router {
+    Router::new()
+        .route("/uv_lamp/devices", post(create_device))
+        .route(
+            "/uv_lamp/devices/:device_number",
+            put(update_device).delete(decommission_device),
+        )
+        .route("/uv_lamp/groups", post(create_group))
+        .route("/uv_lamp/groups/:id", put(update_group).delete(delete_group))
+        .route("/uv_lamp/groups/:id/devices", post(add_group_devices))
+        .route(
+            "/uv_lamp/groups/:id/devices/:device_number",
+            delete(remove_group_device),
+        )
+        .route("/uv_lamp/schedules", post(create_schedule))
+        .route(
+            "/uv_lamp/schedules/:id",
+            put(update_schedule).delete(delete_schedule),
+        )
+        .route("/uv_lamp/notify_jobs", get(list_notify_jobs))
+        .route("/uv_lamp/notify_jobs/:id", get(show_notify_job))
+        .route("/uv_lamp/notify_jobs/:id/retry", post(retry_notify_job))
+        .route("/uv_lamp/notify_jobs/:id/cancel", post(cancel_notify_job))
+        .route_layer(middleware::from_fn_with_state(Role::Admin, require_role))
 }
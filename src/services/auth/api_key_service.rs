@@ -0,0 +1,109 @@
+use crate::repositories::api_keys::{ApiKey, ApiKeys};
+use crate::services::auth::auth_service::{CallerKind, CurrentUser, Role};
+use crate::utils::error::AppError;
+use crate::utils::password::{hash_password, verify_password};
+use anyhow::anyhow;
+use chrono::{Duration, Utc};
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use tracing::{error, info};
+
+pub struct ApiKeyService;
+
+const KEY_PREFIX: &str = "cx_";
+const PREFIX_LENGTH: usize = 8;
+const SECRET_LENGTH: usize = 32;
+
+impl ApiKeyService {
+    /// 生成新的 API 密钥，明文只在创建时返回一次，数据库中只保存哈希
+    pub async fn issue(
+        name: String,
+        role: Role,
+        expires_in_days: Option<u32>,
+        created_by: u64,
+    ) -> Result<(ApiKey, String), anyhow::Error> {
+        let prefix = Self::random_string(PREFIX_LENGTH);
+        let secret = Self::random_string(SECRET_LENGTH);
+        let plaintext = format!("{}{}_{}", KEY_PREFIX, prefix, secret);
+
+        let key_hash = hash_password(&plaintext).map_err(|e| anyhow!(e))?;
+        let expires_at = expires_in_days.map(|days| Utc::now() + Duration::days(days as i64));
+        let id = ApiKeys::create(
+            name.clone(),
+            prefix,
+            key_hash,
+            role.to_string(),
+            created_by,
+            expires_at,
+        )
+        .await?;
+        info!("Issued API key {} ({}) with role {} by user {}", id, name, role, created_by);
+
+        Ok((Self::find(id).await?, plaintext))
+    }
+
+    pub async fn list() -> Result<Vec<ApiKey>, anyhow::Error> {
+        ApiKeys::find_all().await
+    }
+
+    pub async fn find(id: u64) -> Result<ApiKey, anyhow::Error> {
+        ApiKeys::find_by_id(id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("API key {} not found", id)).into())
+    }
+
+    pub async fn revoke(id: u64) -> Result<ApiKey, anyhow::Error> {
+        let key = Self::find(id).await?;
+        // 并发吊销时只有一个请求能更新成功
+        if key.revoked_at.is_some() || ApiKeys::revoke(id).await? == 0 {
+            return Err(AppError::Conflict(format!("API key {} is already revoked", id)).into());
+        }
+        info!("Revoked API key {}", id);
+        Self::find(id).await
+    }
+
+    pub async fn authenticate(plaintext: &str) -> Result<CurrentUser, anyhow::Error> {
+        let prefix = plaintext
+            .strip_prefix(KEY_PREFIX)
+            .and_then(|key| key.split_once('_'))
+            .map(|(prefix, _)| prefix)
+            .ok_or_else(|| anyhow!("Malformed API key"))?;
+        let key = ApiKeys::find_by_prefix(prefix)
+            .await?
+            .ok_or_else(|| anyhow!("API key not found"))?;
+        if key.revoked_at.is_some() {
+            return Err(anyhow!("API key {} is revoked", key.id));
+        }
+        if key.expires_at.is_some_and(|expires_at| expires_at <= Utc::now()) {
+            return Err(anyhow!("API key {} is expired", key.id));
+        }
+
+        // argon2 校验比较耗时，放到阻塞线程池中执行
+        let plaintext = plaintext.to_string();
+        let key_hash = key.key_hash.clone();
+        let matched =
+            tokio::task::spawn_blocking(move || verify_password(&plaintext, &key_hash)).await??;
+        if !matched {
+            return Err(anyhow!("API key {} does not match", key.id));
+        }
+
+        if let Err(e) = ApiKeys::update_last_used_at(key.id).await {
+            error!("Failed to update last used time of API key {}: {}", key.id, e);
+        }
+
+        Ok(CurrentUser {
+            id: key.id,
+            role: key.role.parse()?,
+            username: key.name,
+            kind: CallerKind::ApiKey,
+        })
+    }
+
+    fn random_string(length: usize) -> String {
+        rand::thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(length)
+            .map(char::from)
+            .collect()
+    }
+}
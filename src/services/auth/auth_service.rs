@@ -0,0 +1,191 @@
+use crate::repositories::users::{User, Users};
+use crate::utils::error::AppError;
+use crate::utils::jwt::{create_token, verify_token};
+use crate::utils::password::{hash_password, verify_password};
+use anyhow::anyhow;
+use once_cell::sync::OnceCell;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use tracing::info;
+use utoipa::ToSchema;
+
+pub struct AuthService;
+
+// 用户名不存在时同样校验一次密码，避免通过响应时间判断用户名是否存在
+static DUMMY_PASSWORD_HASH: OnceCell<String> = OnceCell::new();
+
+fn dummy_password_hash() -> Result<&'static str, anyhow::Error> {
+    DUMMY_PASSWORD_HASH
+        .get_or_try_init(|| hash_password("dummy-password").map_err(|e| anyhow!(e)))
+        .map(String::as_str)
+}
+
+/// 角色按权限从低到高排列，高权限角色拥有低权限角色的全部权限
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum Role {
+    // 只能查询状态
+    Viewer,
+    // 可以开关灯
+    Operator,
+    // 可以管理设备、密钥和消毒计划
+    Admin,
+}
+
+impl Display for Role {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Role::Viewer => write!(f, "VIEWER"),
+            Role::Operator => write!(f, "OPERATOR"),
+            Role::Admin => write!(f, "ADMIN"),
+        }
+    }
+}
+
+impl FromStr for Role {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "VIEWER" => Ok(Role::Viewer),
+            "OPERATOR" => Ok(Role::Operator),
+            "ADMIN" => Ok(Role::Admin),
+            _ => Err(anyhow!("Invalid role '{}'", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CallerKind {
+    // 通过登录 token 认证的用户
+    User,
+    // 通过 API 密钥认证的后台系统
+    ApiKey,
+}
+
+/// 通过认证的调用方，由认证中间件放入请求扩展中；API 密钥调用时 `id`/`username` 为密钥的 ID 和名称
+#[derive(Debug, Clone)]
+pub struct CurrentUser {
+    pub id: u64,
+    pub username: String,
+    pub role: Role,
+    pub kind: CallerKind,
+}
+
+impl CurrentUser {
+    /// 区分用户和 API 密钥的调用方标识，如 `user:1`、`api_key:1`
+    pub fn principal(&self) -> String {
+        match self.kind {
+            CallerKind::User => format!("user:{}", self.id),
+            CallerKind::ApiKey => format!("api_key:{}", self.id),
+        }
+    }
+}
+
+impl AuthService {
+    /// 用户名或密码错误时返回 [`AppError::Unauthorized`]
+    pub async fn login(username: &str, password: &str) -> Result<String, anyhow::Error> {
+        let user = Users::find_by_username(username)
+            .await?
+            .filter(|user| user.is_disabled == 0);
+        let password_hash = user.as_ref().map(|user| user.password_hash.clone());
+        let password = password.to_string();
+        let matched = tokio::task::spawn_blocking(move || match password_hash {
+            Some(password_hash) => verify_password(&password, &password_hash),
+            None => verify_password(&password, dummy_password_hash()?).map(|_| false),
+        })
+        .await??;
+        let user = match user {
+            Some(user) if matched => user,
+            _ => {
+                return Err(
+                    AppError::Unauthorized("Invalid username or password".to_string()).into(),
+                )
+            }
+        };
+
+        let token = create_token(&user.id.to_string(), &Self::secret()?)?;
+        Users::update_last_login_at(user.id).await?;
+        info!("User {} logged in", user.username);
+
+        Ok(token)
+    }
+
+    /// 校验 token，并确认对应用户仍然存在且未被禁用
+    pub async fn authenticate(token: &str) -> Result<CurrentUser, anyhow::Error> {
+        let claims = verify_token(token, &Self::secret()?)?;
+        let id: u64 = claims
+            .sub
+            .parse()
+            .map_err(|_| anyhow!("Invalid token subject"))?;
+        let user = Users::find_by_id(id)
+            .await?
+            .filter(|user| user.is_disabled == 0)
+            .ok_or_else(|| anyhow!("User not found or disabled"))?;
+
+        Self::current_user(user)
+    }
+
+    /// 用户表为空时，按环境变量创建初始管理员
+    pub async fn ensure_admin() -> Result<(), anyhow::Error> {
+        if Users::count().await? > 0 {
+            return Ok(());
+        }
+        let (username, password) = match (
+            std::env::var("ADMIN_USERNAME"),
+            std::env::var("ADMIN_PASSWORD"),
+        ) {
+            (Ok(username), Ok(password)) => (username, password),
+            _ => return Err(anyhow!("No users exist and ADMIN_USERNAME/ADMIN_PASSWORD are not set")),
+        };
+        let password_hash = hash_password(&password).map_err(|e| anyhow!(e))?;
+        let id = Users::create(username.clone(), password_hash, Role::Admin.to_string()).await?;
+        info!("Created initial admin user {}, id {}", username, id);
+        Ok(())
+    }
+
+    pub async fn create_user(
+        username: String,
+        password: &str,
+        role: Role,
+    ) -> Result<User, anyhow::Error> {
+        if Users::find_by_username(&username).await?.is_some() {
+            return Err(AppError::Conflict(format!("User {} already exists", username)).into());
+        }
+        let password_hash = hash_password(password).map_err(|e| anyhow!(e))?;
+        let id = Users::create(username.clone(), password_hash, role.to_string()).await?;
+        info!("Created user {} with role {}, id {}", username, role, id);
+        Self::find_user(id).await
+    }
+
+    pub async fn list_users() -> Result<Vec<User>, anyhow::Error> {
+        Users::find_all().await
+    }
+
+    pub async fn find_user(id: u64) -> Result<User, anyhow::Error> {
+        Users::find_by_id(id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("User {} not found", id)).into())
+    }
+
+    pub async fn update_user(id: u64, role: Role, is_disabled: bool) -> Result<User, anyhow::Error> {
+        Self::find_user(id).await?;
+        Users::update(id, role.to_string(), is_disabled).await?;
+        info!("Updated user {}: role {}, disabled {}", id, role, is_disabled);
+        Self::find_user(id).await
+    }
+
+    fn current_user(user: User) -> Result<CurrentUser, anyhow::Error> {
+        Ok(CurrentUser {
+            id: user.id,
+            role: user.role.parse()?,
+            username: user.username,
+            kind: CallerKind::User,
+        })
+    }
+
+    fn secret() -> Result<String, anyhow::Error> {
+        std::env::var("SECRET_KEY").map_err(|_| anyhow!("SECRET_KEY is not configured"))
+    }
+}
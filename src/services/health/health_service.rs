@@ -0,0 +1,124 @@
+use crate::cron::cron_task_manager;
+use crate::tasks::task_manager;
+use crate::utils::mqtt;
+use crate::utils::mqtt_connection::ConnectionState;
+use crate::utils::mysql::MySql;
+use std::time::Duration;
+use tokio::time::timeout;
+
+// 数据库检查的超时时间，避免连接池耗尽时就绪检查一直挂起
+const MYSQL_CHECK_TIMEOUT: Duration = Duration::from_secs(3);
+
+pub struct HealthService;
+
+/// 单个依赖的检查结果，`reason` 为不可用的原因
+pub struct DependencyCheck {
+    pub reason: Option<String>,
+}
+
+impl DependencyCheck {
+    fn ok() -> Self {
+        DependencyCheck { reason: None }
+    }
+
+    fn failed(reason: String) -> Self {
+        DependencyCheck {
+            reason: Some(reason),
+        }
+    }
+
+    pub fn is_healthy(&self) -> bool {
+        self.reason.is_none()
+    }
+}
+
+pub struct Readiness {
+    pub mysql: DependencyCheck,
+    pub mqtt: DependencyCheck,
+    pub tasks: DependencyCheck,
+    pub cron_tasks: DependencyCheck,
+}
+
+impl Readiness {
+    pub fn is_ready(&self) -> bool {
+        self.mysql.is_healthy()
+            && self.mqtt.is_healthy()
+            && self.tasks.is_healthy()
+            && self.cron_tasks.is_healthy()
+    }
+}
+
+impl HealthService {
+    pub async fn check() -> Readiness {
+        Readiness {
+            mysql: Self::check_mysql().await,
+            mqtt: Self::check_mqtt(),
+            tasks: Self::check_tasks().await,
+            cron_tasks: Self::check_cron_tasks().await,
+        }
+    }
+
+    async fn check_mysql() -> DependencyCheck {
+        let result = timeout(MYSQL_CHECK_TIMEOUT, async {
+            MySql::get_instance().await?.ping().await
+        })
+        .await;
+        match result {
+            Ok(Ok(())) => DependencyCheck::ok(),
+            Ok(Err(e)) => DependencyCheck::failed(e.to_string()),
+            Err(_) => DependencyCheck::failed(format!(
+                "No response within {} seconds",
+                MYSQL_CHECK_TIMEOUT.as_secs()
+            )),
+        }
+    }
+
+    fn check_mqtt() -> DependencyCheck {
+        let handler = match mqtt::instance() {
+            Some(handler) => handler,
+            None => return DependencyCheck::failed("MQTT handler is not initialized".to_string()),
+        };
+        let status = handler.connection_status();
+        match status.state {
+            ConnectionState::Connected => DependencyCheck::ok(),
+            ConnectionState::Connecting => DependencyCheck::failed("MQTT is connecting".to_string()),
+            ConnectionState::Disconnected => DependencyCheck::failed(format!(
+                "MQTT disconnected after {} reconnect attempts: {}",
+                status.reconnect_attempts,
+                status.last_error.unwrap_or_default()
+            )),
+        }
+    }
+
+    async fn check_tasks() -> DependencyCheck {
+        let manager = match task_manager::instance() {
+            Some(manager) => manager,
+            None => return DependencyCheck::failed("Task manager is not initialized".to_string()),
+        };
+        let (running, registered) = manager.running_tasks().await;
+        if running < registered {
+            DependencyCheck::failed(format!(
+                "{} of {} background tasks are not running",
+                registered - running,
+                registered
+            ))
+        } else {
+            DependencyCheck::ok()
+        }
+    }
+
+    async fn check_cron_tasks() -> DependencyCheck {
+        let manager = match cron_task_manager::instance() {
+            Some(manager) => manager,
+            None => {
+                return DependencyCheck::failed("Cron task manager is not initialized".to_string())
+            }
+        };
+        let stopped = manager.stopped_tasks().await;
+        if stopped.is_empty() {
+            DependencyCheck::ok()
+        } else {
+            DependencyCheck::failed(format!("Cron tasks not running: {}", stopped.join(", ")))
+        }
+    }
+}
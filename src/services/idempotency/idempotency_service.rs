@@ -0,0 +1,115 @@
+use crate::repositories::idempotency_keys::IdempotencyKeys;
+use crate::utils::error::AppError;
+use anyhow::anyhow;
+use chrono::Utc;
+use std::time::Duration;
+
+/// 处理中记录的租约时长，处理期间每隔 `LOCK_RENEW_INTERVAL` 续期一次，
+/// 租约到期仍未完成的记录视为进程中断后的遗留，允许重新处理
+const LOCK_LEASE: Duration = Duration::from_secs(30);
+pub const LOCK_RENEW_INTERVAL: Duration = Duration::from_secs(10);
+
+pub struct IdempotencyService;
+
+pub enum Idempotency {
+    /// 首次请求，处理完成后需要调用 `complete` 或 `abandon`
+    Started(u64),
+    /// 重复请求，返回首次请求的结果
+    Replay { status_code: u16, response: String },
+}
+
+impl IdempotencyService {
+    /// key 按调用方隔离，不同调用方使用相同的 key 互不影响。
+    /// `replace_on_mismatch` 时参数不同的请求替换已完成的记录，用于按 `message_id` 生成的 key
+    pub async fn begin(
+        principal: &str,
+        path: &str,
+        idempotency_key: &str,
+        fingerprint: &str,
+        ttl: Duration,
+        replace_on_mismatch: bool,
+    ) -> Result<Idempotency, anyhow::Error> {
+        let now = Utc::now();
+        let expires_at = now + chrono::Duration::from_std(ttl)?;
+        let locked_until = now + chrono::Duration::from_std(LOCK_LEASE)?;
+
+        // 旧记录过期时删除后再插入一次
+        for _ in 0..2 {
+            if let Some(id) =
+                IdempotencyKeys::try_create(
+                principal,
+                path,
+                idempotency_key,
+                fingerprint,
+                locked_until,
+                expires_at,
+            )
+            .await?
+            {
+                return Ok(Idempotency::Started(id));
+            }
+            let existing = match IdempotencyKeys::find(principal, path, idempotency_key).await? {
+                Some(existing) => existing,
+                None => continue,
+            };
+            let is_stale = existing.status_code.is_none() && existing.locked_until <= now;
+            let is_replaced = replace_on_mismatch
+                && existing.status_code.is_some()
+                && existing.fingerprint != fingerprint;
+            if existing.expires_at <= now || is_stale || is_replaced {
+                IdempotencyKeys::delete(existing.id).await?;
+                continue;
+            }
+            if existing.fingerprint != fingerprint {
+                return Err(AppError::Conflict(format!(
+                    "Idempotency key {} was used with different parameters",
+                    idempotency_key
+                ))
+                .into());
+            }
+            if let (Some(status_code), Some(response)) = (existing.status_code, existing.response) {
+                return Ok(Idempotency::Replay {
+                    status_code,
+                    response,
+                });
+            }
+            break;
+        }
+        Err(AppError::Conflict(format!(
+            "Request with idempotency key {} is still in progress",
+            idempotency_key
+        ))
+        .into())
+    }
+
+    /// 续期处理中的记录，避免耗时较长的请求被当作遗留记录删除
+    pub async fn renew(id: u64) -> Result<(), anyhow::Error> {
+        let locked_until = Utc::now() + chrono::Duration::from_std(LOCK_LEASE)?;
+        if IdempotencyKeys::extend_lock(id, locked_until).await? == 0 {
+            return Err(anyhow!("Idempotency record {} no longer exists", id));
+        }
+        Ok(())
+    }
+
+    /// 保存首次请求的结果，之后相同 key 的请求直接返回该结果；`keep_for` 可以缩短结果的保留时间
+    pub async fn complete(
+        id: u64,
+        status_code: u16,
+        response: &str,
+        keep_for: Option<Duration>,
+    ) -> Result<(), anyhow::Error> {
+        let expires_at = match keep_for {
+            Some(keep_for) => Some(Utc::now() + chrono::Duration::from_std(keep_for)?),
+            None => None,
+        };
+        if IdempotencyKeys::complete(id, status_code, response, expires_at).await? == 0 {
+            return Err(anyhow!("Idempotency record {} no longer exists", id));
+        }
+        Ok(())
+    }
+
+    /// 请求没有产生需要保留的结果，删除记录以便客户端重试
+    pub async fn abandon(id: u64) -> Result<(), anyhow::Error> {
+        IdempotencyKeys::delete(id).await
+    }
+}
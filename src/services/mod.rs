@@ -1 +1,4 @@
+pub mod auth;
+pub mod health;
+pub mod idempotency;
 pub mod uv_lamp;
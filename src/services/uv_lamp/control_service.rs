@@ -1,36 +1,318 @@
-use crate::params::requests::uv_lamp::TurnParams;
-use crate::repositories::uv_lamp_mqtt_message::UVLampMqttMessage;
+use crate::params::requests::common::Pagination;
+use crate::params::requests::uv_lamp::{
+    BatchTurnParams, ListCommandsParams, TurnParams, MAX_BATCH_TARGETS,
+};
+use crate::protocol::command::{Command, CommandAck};
+use crate::protocol::common::{LampStatus, MessageId, Reason};
+use crate::repositories::uv_lamp_mqtt_message::{Message, MessageFilter, UVLampMqttMessage};
+use crate::services::uv_lamp::device_service::DeviceService;
+use crate::services::uv_lamp::group_service::GroupService;
 use crate::utils;
-use serde_json::json;
-use tracing::{error, info};
+use crate::utils::command_guard::get_command_guard;
+use crate::utils::error::AppError;
+use crate::utils::mqtt::get_device_manager;
+use crate::utils::request_id;
+use crate::utils::topic::{get_topic_config, CHANNEL_COMMAND};
+use futures::stream::FuturesUnordered;
+use futures::StreamExt;
+use rand::Rng;
+use serde::Serialize;
+use serde_json::Value;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Semaphore;
+use tracing::{error, info, Instrument};
+use utoipa::ToSchema;
+use validator::Validate;
 
 pub struct ControlService;
 
+/// 设备对开关指令的回复
+#[derive(Debug)]
+pub struct TurnReply {
+    pub message_id: i32,
+    pub status: Option<LampStatus>,
+    pub reason: Option<Reason>,
+    pub payload: Value,
+}
+
+/// 批量开关中单个设备的下发结果
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BatchTurnResult {
+    pub device_number: String,
+    pub message_id: i32,
+    pub success: bool,
+    /// 失败时的错误码，与接口错误的 `code` 一致
+    pub error_code: Option<i32>,
+    pub error: Option<String>,
+}
+
 impl ControlService {
     pub async fn turn(params: TurnParams) -> Result<i32, anyhow::Error> {
-        let topic = Self::get_topic(&params.device_number)?;
-        info!("Topic is {}", topic);
+        let mqtt_handler = Self::mqtt_handler()?;
+        let device_number = Self::device_number(&params)?;
+        Self::publish(&mqtt_handler, device_number, &params).await?;
+
+        Ok(params.message_id)
+    }
+
+    /// 向分组及其下级分组中的设备下发开关指令，每台设备都使用调用方传入的 `message_id`，
+    /// 结果与批量开关相同，按设备返回
+    pub async fn turn_group(params: TurnParams) -> Result<Vec<BatchTurnResult>, anyhow::Error> {
+        let mqtt_handler = Self::mqtt_handler()?;
+        let group_id = match (&params.device_number, params.group_id) {
+            (None, Some(group_id)) => group_id,
+            _ => return Err(Self::target_required().into()),
+        };
+
+        let device_numbers = GroupService::resolve_targets(Vec::new(), Some(group_id)).await?;
+        Self::check_targets(&device_numbers)?;
+
+        Ok(Self::fan_out(
+            mqtt_handler,
+            device_numbers,
+            Some(params.message_id),
+            params.status,
+            params.duration,
+        )
+        .await)
+    }
 
-        let message = json!({
-            "id": params.message_id,
-            "s": if params.status { 1 } else { 0 },
-            "d": params.duration,
+    /// 下发开关指令并等待设备回复，超时返回 [`AppError::ReplyTimeout`]；
+    /// 已知离线的设备不会回复，直接返回 [`AppError::DeviceOffline`]
+    pub async fn turn_and_wait(
+        params: TurnParams,
+        timeout: Duration,
+    ) -> Result<TurnReply, anyhow::Error> {
+        let mqtt_handler = Self::mqtt_handler()?;
+        let device_number = Self::device_number(&params)?;
+        let is_offline = match get_device_manager().lock().await.get_device(device_number) {
+            Some(device) => !device.is_online(),
+            None => false,
+        };
+        if is_offline {
+            return Err(AppError::DeviceOffline(format!(
+                "Device {} is offline",
+                device_number
+            ))
+            .into());
+        }
+
+        let waiter = mqtt_handler
+            .wait_for_reply(device_number, &params.message_id.to_string())?;
+        Self::publish(&mqtt_handler, device_number, &params).await?;
+
+        let payload = waiter.wait(timeout).await.ok_or_else(|| {
+            AppError::ReplyTimeout(format!(
+                "Device {} did not reply to message {} within {}ms",
+                device_number,
+                params.message_id,
+                timeout.as_millis()
+            ))
+        })?;
+        info!(
+            "Device {} replied to message {}: {}",
+            device_number, params.message_id, payload
+        );
+
+        let reply: Option<CommandAck> = serde_json::from_str(&payload).ok();
+        Ok(TurnReply {
+            message_id: params.message_id,
+            status: reply.as_ref().and_then(|reply| reply.status),
+            reason: reply.as_ref().and_then(|reply| reply.reason),
+            payload: serde_json::from_str(&payload).unwrap_or(Value::String(payload)),
         })
-        .to_string();
+    }
+
+    /// 向多台设备下发同一开关指令，每台设备单独生成消息 ID，
+    /// 单台设备失败不影响其他设备，结果按请求中的设备顺序返回
+    pub async fn batch_turn(params: BatchTurnParams) -> Result<Vec<BatchTurnResult>, anyhow::Error> {
+        let mqtt_handler = Self::mqtt_handler()?;
 
-        if let Some(mqtt_handler) = utils::mqtt::instance() {
-            mqtt_handler.send(topic.as_str(), message.clone()).await?;
-            UVLampMqttMessage::create(params.message_id.to_string(), params.device_number, message)
-                .await?;
-        } else {
-            error!("MQTT Handler not initialized!")
+        let device_numbers =
+            GroupService::resolve_targets(params.device_numbers, params.group_id).await?;
+        Self::check_targets(&device_numbers)?;
+
+        Ok(Self::fan_out(mqtt_handler, device_numbers, None, params.status, params.duration).await)
+    }
+
+    fn check_targets(device_numbers: &[String]) -> Result<(), AppError> {
+        if device_numbers.is_empty() {
+            return Err(AppError::BadRequest("No target devices to turn".to_string()));
         }
+        if device_numbers.len() > MAX_BATCH_TARGETS {
+            return Err(AppError::BadRequest(format!(
+                "At most {} devices can be turned at once, got {}",
+                MAX_BATCH_TARGETS,
+                device_numbers.len()
+            )));
+        }
+        Ok(())
+    }
 
-        Ok(params.message_id)
+    /// 并发向多台设备下发同一开关指令，未指定 `message_id` 时每台设备单独生成
+    async fn fan_out(
+        mqtt_handler: Arc<utils::mqtt::MqttHandler>,
+        device_numbers: Vec<String>,
+        message_id: Option<i32>,
+        status: bool,
+        duration: i32,
+    ) -> Vec<BatchTurnResult> {
+        let concurrency_limit = std::env::var("UV_LAMP_BATCH_TURN_CONCURRENCY")
+            .unwrap_or_else(|_| "10".to_string())
+            .parse::<usize>()
+            .unwrap_or(10)
+            .max(1);
+        let semaphore = Arc::new(Semaphore::new(concurrency_limit));
+
+        // spawn 的任务不继承请求上下文，需要显式带上请求 ID
+        let request_id = request_id::current();
+        let mut futures = FuturesUnordered::new();
+        for (index, device_number) in device_numbers.into_iter().enumerate() {
+            let mqtt_handler = mqtt_handler.clone();
+            let semaphore = semaphore.clone();
+            let params = TurnParams {
+                message_id: message_id
+                    .unwrap_or_else(|| rand::thread_rng().gen_range(100_000..1_000_000)),
+                device_number: Some(device_number.clone()),
+                group_id: None,
+                status,
+                duration,
+            };
+
+            // 任务 panic 时仍需返回该设备的结果
+            let message_id = params.message_id;
+            let request_id = request_id.clone();
+            let task = async move {
+                let device_number = params.device_number.clone().unwrap_or_default();
+                let _permit = semaphore.acquire().await;
+                let result = match params.validate() {
+                    Ok(_) => Self::publish(&mqtt_handler, &device_number, &params).await,
+                    Err(e) => Err(AppError::BadRequest(format!("Invalid parameters: {}", e)).into()),
+                };
+                let error = result.err().map(AppError::from);
+                if let Some(e) = &error {
+                    error!("Batch turn device {} failed: {}", device_number, e);
+                }
+                (
+                    index,
+                    BatchTurnResult {
+                        device_number,
+                        message_id: params.message_id,
+                        success: error.is_none(),
+                        error_code: error.as_ref().map(AppError::code),
+                        error: error.map(|e| e.to_string()),
+                    },
+                )
+            };
+            let handle = tokio::spawn(request_id::scope(request_id, task).in_current_span());
+            futures.push(async move {
+                handle.await.unwrap_or_else(|e| {
+                    error!("Batch turn task of device {} panicked: {}", device_number, e);
+                    let error = AppError::Internal(format!("Batch turn task failed: {}", e));
+                    (
+                        index,
+                        BatchTurnResult {
+                            device_number,
+                            message_id,
+                            success: false,
+                            error_code: Some(error.code()),
+                            error: Some(error.to_string()),
+                        },
+                    )
+                })
+            });
+        }
+
+        let mut results = Vec::new();
+        while let Some(result) = futures.next().await {
+            results.push(result);
+        }
+        results.sort_by_key(|(index, _)| *index);
+
+        results.into_iter().map(|(_, result)| result).collect()
+    }
+
+    /// `device_number` 与 `group_id` 二选一，按单台设备下发时取设备编号
+    fn device_number(params: &TurnParams) -> Result<&str, AppError> {
+        match (&params.device_number, params.group_id) {
+            (Some(device_number), None) => Ok(device_number),
+            _ => Err(Self::target_required()),
+        }
+    }
+
+    fn target_required() -> AppError {
+        AppError::BadRequest("Exactly one of device_number or group_id is required".to_string())
+    }
+
+    async fn publish(
+        mqtt_handler: &utils::mqtt::MqttHandler,
+        device_number: &str,
+        params: &TurnParams,
+    ) -> Result<(), anyhow::Error> {
+        let topic = Self::get_topic(device_number).await?;
+        info!("Topic is {}", topic);
+
+        let message_id = params.message_id.to_string();
+        let guard = get_command_guard();
+        guard.acquire(device_number, &message_id, params.status)?;
+
+        let message = serde_json::to_string(&Command {
+            id: MessageId::Number(params.message_id as u64),
+            turn_on: params.status,
+            duration: params.duration,
+        })?;
+
+        if let Err(e) = mqtt_handler.send(topic.as_str(), message.clone()).await {
+            guard.release(device_number, &message_id);
+            return Err(
+                AppError::MqttUnavailable(format!("Failed to publish to {}: {}", topic, e)).into(),
+            );
+        }
+        UVLampMqttMessage::create(
+            message_id,
+            device_number.to_string(),
+            message,
+            request_id::current(),
+        )
+        .await?;
+        Ok(())
+    }
+
+    pub async fn list_commands(
+        params: ListCommandsParams,
+    ) -> Result<(Vec<Message>, i64, Pagination), anyhow::Error> {
+        let pagination = Pagination::new(params.page, params.page_size);
+        let device_numbers = match params.group_id {
+            Some(group_id) => Some(GroupService::resolve_device_numbers(group_id).await?),
+            None => None,
+        };
+        let filter = MessageFilter {
+            device_number: params.device_number,
+            device_numbers,
+            message_id: params.message_id,
+            is_acked: params.is_acked,
+        };
+        let (messages, total) = UVLampMqttMessage::find_page(&filter, pagination).await?;
+        Ok((messages, total, pagination))
+    }
+
+    /// 连接断开时直接拒绝，不把指令堆积在发送队列中
+    fn mqtt_handler() -> Result<Arc<utils::mqtt::MqttHandler>, AppError> {
+        let mqtt_handler = utils::mqtt::instance()
+            .ok_or_else(|| AppError::MqttUnavailable("MQTT handler is not initialized".to_string()))?;
+        if !mqtt_handler.is_connected() {
+            let status = mqtt_handler.connection_status();
+            return Err(AppError::MqttUnavailable(format!(
+                "MQTT is not connected: {}",
+                status.last_error.as_deref().unwrap_or("connecting")
+            )));
+        }
+        Ok(mqtt_handler)
     }
 
-    fn get_topic(device_number: &str) -> Result<String, anyhow::Error> {
-        let topic = format!("87855294541367dab3e244c2441c5f22/{}/oc/s", device_number);
-        Ok(topic)
+    async fn get_topic(device_number: &str) -> Result<String, anyhow::Error> {
+        let product_key = DeviceService::product_key(device_number).await?;
+        Ok(get_topic_config().topic(&product_key, device_number, CHANNEL_COMMAND))
     }
 }
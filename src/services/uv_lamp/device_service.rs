@@ -0,0 +1,95 @@
+use crate::params::requests::uv_lamp_device::{CreateDeviceParams, UpdateDeviceParams};
+use crate::repositories::uv_lamp_devices::{Device, UVLampDevice};
+use crate::utils::error::AppError;
+use crate::utils::mqtt::get_device_manager;
+use crate::utils::topic::get_topic_config;
+use tracing::info;
+
+pub struct DeviceService;
+
+impl DeviceService {
+    pub async fn create(params: CreateDeviceParams) -> Result<Device, anyhow::Error> {
+        let existing = UVLampDevice::find_by_device_number(&params.device_number).await?;
+        if existing.as_ref().is_some_and(|device| device.is_decommissioned == 0) {
+            return Err(AppError::Conflict(format!("Device {} already exists", params.device_number)).into());
+        }
+
+        let product_key = Self::check_product_key(params.product_key)?;
+        // 停用的设备重新注册时恢复启用，沿用原来的记录
+        if existing.is_some() {
+            UVLampDevice::recommission(
+                &params.device_number,
+                params.name,
+                params.remark,
+                product_key,
+            )
+            .await?;
+            info!("Recommissioned device {}", params.device_number);
+            return Self::find(&params.device_number).await;
+        }
+        let id = UVLampDevice::create(
+            params.device_number.clone(),
+            params.name,
+            params.remark,
+            product_key,
+        )
+        .await?;
+        info!("Registered device {}, id {}", params.device_number, id);
+
+        Self::find(&params.device_number).await
+    }
+
+    pub async fn list(include_decommissioned: bool) -> Result<Vec<Device>, anyhow::Error> {
+        UVLampDevice::find_all(include_decommissioned).await
+    }
+
+    pub async fn find(device_number: &str) -> Result<Device, anyhow::Error> {
+        UVLampDevice::find_by_device_number(device_number)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Device {} not found", device_number)).into())
+    }
+
+    pub async fn update(
+        device_number: &str,
+        params: UpdateDeviceParams,
+    ) -> Result<Device, anyhow::Error> {
+        Self::find(device_number).await?;
+        let product_key = Self::check_product_key(params.product_key)?;
+        UVLampDevice::update(device_number, params.name, params.remark, product_key).await?;
+        Self::find(device_number).await
+    }
+
+    /// 设备所属产品，未注册或未指定产品的设备返回空字符串，即使用默认产品
+    pub async fn product_key(device_number: &str) -> Result<String, anyhow::Error> {
+        let device = UVLampDevice::find_by_device_number(device_number).await?;
+        Ok(device.map(|device| device.product_key).unwrap_or_default())
+    }
+
+    fn check_product_key(product_key: Option<String>) -> Result<String, AppError> {
+        match product_key {
+            Some(product_key) if !get_topic_config().contains_product_key(&product_key) => Err(
+                AppError::BadRequest(format!("Unknown product key {}", product_key)),
+            ),
+            Some(product_key) => Ok(product_key),
+            None => Ok(String::new()),
+        }
+    }
+
+    pub async fn decommission(device_number: &str) -> Result<Device, anyhow::Error> {
+        if Self::find(device_number).await?.is_decommissioned == 1 {
+            return Err(AppError::Conflict(format!(
+                "Device {} is already decommissioned",
+                device_number
+            ))
+            .into());
+        }
+        UVLampDevice::decommission(device_number).await?;
+
+        // 停用后不再轮询在线状态，也不再发送离线通知
+        let manager = get_device_manager();
+        manager.lock().await.remove_device(device_number);
+        info!("Decommissioned device {}", device_number);
+
+        Self::find(device_number).await
+    }
+}
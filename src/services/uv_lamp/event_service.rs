@@ -0,0 +1,51 @@
+use crate::params::requests::uv_lamp::StreamEventsParams;
+use crate::services::uv_lamp::group_service::GroupService;
+use crate::utils::events::{get_event_bus, LampEvent};
+use futures::stream::{self, Stream};
+use std::collections::HashSet;
+use std::sync::Arc;
+use tokio::sync::broadcast::error::RecvError;
+use tracing::warn;
+
+pub struct EventService;
+
+impl EventService {
+    /// 订阅设备事件，指定设备或分组时只推送这些设备的事件，分组成员在订阅时确定
+    pub async fn subscribe(
+        params: StreamEventsParams,
+    ) -> Result<impl Stream<Item = LampEvent>, anyhow::Error> {
+        let filter = if params.device_number.is_none() && params.group_id.is_none() {
+            None
+        } else {
+            let device_numbers =
+                GroupService::resolve_targets(params.device_number.into_iter().collect(), params.group_id)
+                    .await?;
+            Some(device_numbers.into_iter().collect::<HashSet<String>>())
+        };
+        let filter = Arc::new(filter);
+        let receiver = get_event_bus().subscribe();
+
+        Ok(stream::unfold(receiver, move |mut receiver| {
+            let filter = filter.clone();
+            async move {
+                loop {
+                    match receiver.recv().await {
+                        Ok(event) => {
+                            let matched = match filter.as_ref() {
+                                Some(device_numbers) => device_numbers.contains(event.device_number()),
+                                None => true,
+                            };
+                            if matched {
+                                return Some((event, receiver));
+                            }
+                        }
+                        Err(RecvError::Lagged(skipped)) => {
+                            warn!("Event subscriber lagged, {} events skipped", skipped);
+                        }
+                        Err(RecvError::Closed) => return None,
+                    }
+                }
+            }
+        }))
+    }
+}
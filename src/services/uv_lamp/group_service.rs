@@ -0,0 +1,174 @@
+use crate::params::requests::uv_lamp_group::{CreateGroupParams, UpdateGroupParams};
+use crate::repositories::uv_lamp_device_groups::{Group, UVLampDeviceGroup};
+use crate::repositories::uv_lamp_devices::UVLampDevice;
+use crate::repositories::uv_lamp_schedules::UVLampSchedule;
+use crate::utils::error::AppError;
+use std::collections::VecDeque;
+use tracing::info;
+
+pub struct GroupService;
+
+impl GroupService {
+    pub async fn create(params: CreateGroupParams) -> Result<Group, anyhow::Error> {
+        if params.parent_id != 0 {
+            Self::find(params.parent_id).await?;
+        }
+        let id = UVLampDeviceGroup::create(
+            params.parent_id,
+            params.name,
+            params.kind,
+            params.remark,
+        )
+        .await?;
+        info!("Created device group {}", id);
+
+        Self::find(id).await
+    }
+
+    pub async fn list() -> Result<Vec<Group>, anyhow::Error> {
+        UVLampDeviceGroup::find_all().await
+    }
+
+    pub async fn find(id: u64) -> Result<Group, anyhow::Error> {
+        UVLampDeviceGroup::find_by_id(id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Device group {} not found", id)).into())
+    }
+
+    pub async fn update(id: u64, params: UpdateGroupParams) -> Result<Group, anyhow::Error> {
+        Self::find(id).await?;
+        if params.parent_id != 0 {
+            Self::find(params.parent_id).await?;
+            // 不能把分组挂到自己或自己的下级分组下面，否则会形成环
+            let groups = UVLampDeviceGroup::find_all().await?;
+            if Self::creates_cycle(&groups, id, params.parent_id) {
+                return Err(AppError::Conflict(format!(
+                    "Device group {} cannot be moved under its own subgroup {}",
+                    id, params.parent_id
+                ))
+                .into());
+            }
+        }
+        UVLampDeviceGroup::update(id, params.parent_id, params.name, params.kind, params.remark)
+            .await?;
+
+        Self::find(id).await
+    }
+
+    pub async fn delete(id: u64) -> Result<(), anyhow::Error> {
+        Self::find(id).await?;
+        let groups = UVLampDeviceGroup::find_all().await?;
+        if groups.iter().any(|group| group.parent_id == id) {
+            return Err(AppError::Conflict(format!(
+                "Device group {} still has subgroups, delete them first",
+                id
+            ))
+            .into());
+        }
+        // 计划执行时找不到分组会一直失败，需要先删除或修改计划
+        let schedule_ids = UVLampSchedule::find_ids_by_group_id(id).await?;
+        if !schedule_ids.is_empty() {
+            return Err(AppError::Conflict(format!(
+                "Device group {} is still used by schedules {:?}, update or delete them first",
+                id, schedule_ids
+            ))
+            .into());
+        }
+        UVLampDeviceGroup::delete(id).await?;
+        info!("Deleted device group {}", id);
+        Ok(())
+    }
+
+    pub async fn add_devices(id: u64, device_numbers: Vec<String>) -> Result<u64, anyhow::Error> {
+        Self::find(id).await?;
+        let registered = UVLampDevice::find_active_device_numbers().await?;
+        if let Some(device_number) = device_numbers
+            .iter()
+            .find(|device_number| !registered.contains(device_number))
+        {
+            return Err(AppError::NotFound(format!("Device {} not found", device_number)).into());
+        }
+        UVLampDeviceGroup::add_members(id, &device_numbers).await
+    }
+
+    pub async fn remove_device(id: u64, device_number: &str) -> Result<(), anyhow::Error> {
+        Self::find(id).await?;
+        if UVLampDeviceGroup::remove_member(id, device_number).await? == 0 {
+            return Err(AppError::NotFound(format!(
+                "Device {} is not a member of device group {}",
+                device_number, id
+            ))
+            .into());
+        }
+        Ok(())
+    }
+
+    /// 分组及其所有下级分组中的在用设备
+    pub async fn resolve_device_numbers(id: u64) -> Result<Vec<String>, anyhow::Error> {
+        Self::find(id).await?;
+        let groups = UVLampDeviceGroup::find_all().await?;
+        let mut group_ids = Self::descendant_ids(&groups, id);
+        group_ids.push(id);
+        UVLampDeviceGroup::find_device_numbers(&group_ids).await
+    }
+
+    /// 合并直接指定的设备和分组中的设备，去重并保持原有顺序
+    pub async fn resolve_targets(
+        device_numbers: Vec<String>,
+        group_id: Option<u64>,
+    ) -> Result<Vec<String>, anyhow::Error> {
+        let mut targets = device_numbers;
+        if let Some(group_id) = group_id {
+            targets.extend(Self::resolve_device_numbers(group_id).await?);
+        }
+        let mut seen = std::collections::HashSet::new();
+        targets.retain(|device_number| seen.insert(device_number.clone()));
+        Ok(targets)
+    }
+
+    fn creates_cycle(groups: &[Group], id: u64, parent_id: u64) -> bool {
+        parent_id == id || Self::descendant_ids(groups, id).contains(&parent_id)
+    }
+
+    fn descendant_ids(groups: &[Group], id: u64) -> Vec<u64> {
+        let mut descendants = Vec::new();
+        let mut queue = VecDeque::from([id]);
+        while let Some(parent_id) = queue.pop_front() {
+            for group in groups.iter().filter(|group| group.parent_id == parent_id) {
+                if group.id != id && !descendants.contains(&group.id) {
+                    descendants.push(group.id);
+                    queue.push_back(group.id);
+                }
+            }
+        }
+        descendants
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::GroupService;
+    use crate::repositories::uv_lamp_device_groups::Group;
+    use chrono::Utc;
+
+    fn group(id: u64, parent_id: u64) -> Group {
+        Group {
+            id,
+            parent_id,
+            name: format!("group-{}", id),
+            kind: "BUILDING".to_string(),
+            remark: String::new(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_creates_cycle() {
+        let groups = vec![group(1, 0), group(2, 1), group(3, 2), group(4, 0)];
+        assert!(GroupService::creates_cycle(&groups, 1, 1));
+        assert!(GroupService::creates_cycle(&groups, 1, 3));
+        assert!(!GroupService::creates_cycle(&groups, 3, 1));
+        assert!(!GroupService::creates_cycle(&groups, 1, 4));
+    }
+}
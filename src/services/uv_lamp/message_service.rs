@@ -0,0 +1,42 @@
+use crate::params::requests::common::Pagination;
+use crate::params::requests::uv_lamp::ListMessagesParams;
+use crate::repositories::uv_lamp_mqtt_received_messages::{
+    ReceivedMessage, ReceivedMessageFilter, UVLampMqttReceivedMessages,
+};
+use crate::services::uv_lamp::group_service::GroupService;
+use crate::utils::datetime::parse_datetime;
+use chrono_tz::Tz;
+
+pub struct MessageService;
+
+impl MessageService {
+    /// 分页查询设备上报的消息，时间参数按 `timezone` 解释
+    pub async fn list(
+        params: ListMessagesParams,
+        timezone: &Tz,
+    ) -> Result<(Vec<ReceivedMessage>, i64, Pagination), anyhow::Error> {
+        let pagination = Pagination::new(params.page, params.page_size);
+        let start_time = match &params.start_time {
+            Some(value) => Some(parse_datetime(value, timezone)?),
+            None => None,
+        };
+        let end_time = match &params.end_time {
+            Some(value) => Some(parse_datetime(value, timezone)?),
+            None => None,
+        };
+        let device_numbers = match params.group_id {
+            Some(group_id) => Some(GroupService::resolve_device_numbers(group_id).await?),
+            None => None,
+        };
+        let filter = ReceivedMessageFilter {
+            device_number: params.device_number,
+            device_numbers,
+            kind: params.kind,
+            start_time,
+            end_time,
+            keyword: params.keyword.filter(|keyword| !keyword.is_empty()),
+        };
+        let (messages, total) = UVLampMqttReceivedMessages::find_page(&filter, pagination).await?;
+        Ok((messages, total, pagination))
+    }
+}
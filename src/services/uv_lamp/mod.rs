@@ -1 +1,8 @@
 pub mod control_service;
+pub mod device_service;
+pub mod event_service;
+pub mod group_service;
+pub mod message_service;
+pub mod notify_job_service;
+pub mod schedule_service;
+pub mod status_service;
@@ -0,0 +1,60 @@
+use crate::params::requests::common::Pagination;
+use crate::params::requests::uv_lamp_notify_job::ListNotifyJobsParams;
+use crate::repositories::uv_lamp_mqtt_notify_job::{
+    Job, JobFilter, JobState, UVLampMqttNotifyJob,
+};
+use crate::repositories::uv_lamp_mqtt_notify_job_attempts::{
+    Attempt, UVLampMqttNotifyJobAttempt,
+};
+use crate::utils::error::AppError;
+use tracing::info;
+
+pub struct NotifyJobService;
+
+impl NotifyJobService {
+    pub async fn list(
+        params: ListNotifyJobsParams,
+    ) -> Result<(Vec<Job>, i64, Pagination), anyhow::Error> {
+        let pagination = Pagination::new(params.page, params.page_size);
+        let filter = JobFilter {
+            state: params.state,
+            job_type: params.job_type,
+            device_number: params.device_number,
+        };
+        let (jobs, total) = UVLampMqttNotifyJob::find_page(&filter, pagination).await?;
+        Ok((jobs, total, pagination))
+    }
+
+    pub async fn find(id: u64) -> Result<Job, anyhow::Error> {
+        UVLampMqttNotifyJob::find_by_id(id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Notify job {} not found", id)).into())
+    }
+
+    pub async fn find_with_attempts(id: u64) -> Result<(Job, Vec<Attempt>), anyhow::Error> {
+        let job = Self::find(id).await?;
+        let attempts = UVLampMqttNotifyJobAttempt::find_by_job_id(id).await?;
+        Ok((job, attempts))
+    }
+
+    /// 失败的任务重新进入发送队列
+    pub async fn retry(id: u64) -> Result<Job, anyhow::Error> {
+        let job = Self::find(id).await?;
+        if job.state() != JobState::Failed {
+            return Err(AppError::Conflict(format!("Notify job {} is not in failed state", id)).into());
+        }
+        UVLampMqttNotifyJob::reset_failed(id).await?;
+        info!("Reset failed notify job {}", id);
+        Self::find(id).await
+    }
+
+    pub async fn cancel(id: u64) -> Result<Job, anyhow::Error> {
+        let job = Self::find(id).await?;
+        if job.state() != JobState::Incomplete {
+            return Err(AppError::Conflict(format!("Notify job {} is not pending", id)).into());
+        }
+        UVLampMqttNotifyJob::cancel(id).await?;
+        info!("Cancelled notify job {}", id);
+        Self::find(id).await
+    }
+}
@@ -0,0 +1,102 @@
+use crate::cron::{cron_task_manager, disinfection_schedule};
+use crate::params::requests::uv_lamp_schedule::ScheduleParams;
+use crate::repositories::uv_lamp_schedules::{Schedule, ScheduleFields, UVLampSchedule};
+use crate::services::uv_lamp::device_service::DeviceService;
+use crate::services::uv_lamp::group_service::GroupService;
+use crate::utils::error::AppError;
+use chrono_tz::Tz;
+use tracing::info;
+
+pub struct ScheduleService;
+
+impl ScheduleService {
+    pub async fn create(params: ScheduleParams) -> Result<Schedule, anyhow::Error> {
+        let fields = Self::build_fields(params).await?;
+        let id = UVLampSchedule::create(fields).await?;
+        info!("Created disinfection schedule {}", id);
+
+        disinfection_schedule::reload(id).await?;
+        Self::find(id).await
+    }
+
+    pub async fn list() -> Result<Vec<Schedule>, anyhow::Error> {
+        UVLampSchedule::find_all().await
+    }
+
+    pub async fn find(id: u64) -> Result<Schedule, anyhow::Error> {
+        UVLampSchedule::find_by_id(id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Disinfection schedule {} not found", id)).into())
+    }
+
+    pub async fn update(id: u64, params: ScheduleParams) -> Result<Schedule, anyhow::Error> {
+        Self::find(id).await?;
+        let fields = Self::build_fields(params).await?;
+        UVLampSchedule::update(id, fields).await?;
+        info!("Updated disinfection schedule {}", id);
+
+        disinfection_schedule::reload(id).await?;
+        Self::find(id).await
+    }
+
+    pub async fn delete(id: u64) -> Result<(), anyhow::Error> {
+        Self::find(id).await?;
+        UVLampSchedule::delete(id).await?;
+        info!("Deleted disinfection schedule {}", id);
+
+        disinfection_schedule::reload(id).await
+    }
+
+    /// 未单独指定时区的计划所使用的时区
+    pub fn default_timezone() -> Tz {
+        cron_task_manager::instance()
+            .map(|manager| manager.timezone())
+            .unwrap_or(Tz::UTC)
+    }
+
+    async fn build_fields(params: ScheduleParams) -> Result<ScheduleFields, anyhow::Error> {
+        let weekdays = params
+            .weekdays
+            .unwrap_or_default()
+            .iter()
+            .map(|weekday| weekday.to_string())
+            .collect::<Vec<String>>()
+            .join(",");
+        let cron_expression = params.cron_expression.unwrap_or_default();
+        let time_of_day = params.time_of_day.unwrap_or_default();
+        disinfection_schedule::cron_expression(&cron_expression, &weekdays, &time_of_day)?;
+
+        let timezone = params.timezone.unwrap_or_default();
+        disinfection_schedule::timezone(&timezone, Self::default_timezone())?;
+
+        let (device_number, group_id) = match (params.device_number, params.group_id) {
+            (Some(device_number), None) => {
+                DeviceService::find(&device_number).await?;
+                (device_number, 0)
+            }
+            (None, Some(group_id)) => {
+                GroupService::find(group_id).await?;
+                (String::new(), group_id)
+            }
+            _ => {
+                return Err(AppError::BadRequest(
+                    "Exactly one of device_number or group_id is required".to_string(),
+                )
+                .into())
+            }
+        };
+
+        Ok(ScheduleFields {
+            name: params.name,
+            cron_expression,
+            weekdays,
+            time_of_day,
+            device_number,
+            group_id,
+            status: params.status,
+            duration: params.duration,
+            timezone,
+            is_enabled: params.is_enabled,
+        })
+    }
+}
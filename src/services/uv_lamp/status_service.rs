@@ -0,0 +1,75 @@
+use crate::repositories::uv_lamp_devices::UVLampDevice;
+use crate::services::uv_lamp::device_service::DeviceService;
+use crate::services::uv_lamp::group_service::GroupService;
+use crate::utils::error::AppError;
+use crate::utils::mqtt;
+use crate::utils::mqtt::get_device_manager;
+use crate::utils::mqtt_connection::ConnectionStatus;
+
+pub struct StatusService;
+
+pub struct DeviceStatus {
+    pub device_number: String,
+    pub is_online: bool,
+    pub last_response_time: Option<u64>,
+    pub last_query_time: Option<u64>,
+}
+
+impl StatusService {
+    /// 与 MQTT 服务器的连接状态
+    pub fn mqtt() -> Result<ConnectionStatus, anyhow::Error> {
+        match mqtt::instance() {
+            Some(handler) => Ok(handler.connection_status()),
+            None => Err(
+                AppError::MqttUnavailable("MQTT handler is not initialized".to_string()).into(),
+            ),
+        }
+    }
+
+    pub async fn find(device_number: &str) -> Result<DeviceStatus, anyhow::Error> {
+        let device = DeviceService::find(device_number).await?;
+        let statuses = Self::collect(vec![device.device_number]).await;
+        Ok(statuses.into_iter().next().expect("one status per device"))
+    }
+
+    pub async fn list(
+        online: Option<bool>,
+        group_id: Option<u64>,
+    ) -> Result<Vec<DeviceStatus>, anyhow::Error> {
+        let device_numbers = match group_id {
+            Some(group_id) => GroupService::resolve_device_numbers(group_id).await?,
+            None => UVLampDevice::find_active_device_numbers().await?,
+        };
+        let statuses = Self::collect(device_numbers).await;
+        Ok(statuses
+            .into_iter()
+            .filter(|status| match online {
+                Some(online) => status.is_online == online,
+                None => true,
+            })
+            .collect())
+    }
+
+    // 状态只保存在内存中，尚未轮询到的设备视为离线
+    async fn collect(device_numbers: Vec<String>) -> Vec<DeviceStatus> {
+        let manager = get_device_manager();
+        let manager = manager.lock().await;
+        device_numbers
+            .into_iter()
+            .map(|device_number| match manager.get_device(&device_number) {
+                Some(info) => DeviceStatus {
+                    device_number,
+                    is_online: info.is_online(),
+                    last_response_time: info.last_response_time(),
+                    last_query_time: info.last_query_time(),
+                },
+                None => DeviceStatus {
+                    device_number,
+                    is_online: false,
+                    last_response_time: None,
+                    last_query_time: None,
+                },
+            })
+            .collect()
+    }
+}
@@ -1,8 +1,15 @@
 use std::fmt::{Display, Formatter};
+use std::time::Instant;
 use chrono::Utc;
-use reqwest::Response;
+use reqwest::{RequestBuilder, Response};
+use metrics::{counter, histogram};
 use tracing::{error, info};
 use crate::repositories::uv_lamp_mqtt_notify_job::{Job, UVLampMqttNotifyJob};
+use crate::repositories::uv_lamp_mqtt_notify_job_attempts::UVLampMqttNotifyJobAttempt;
+use crate::utils::metrics::{
+    NOTIFY_JOBS_TOTAL, NOTIFY_JOB_RETRIES_TOTAL, NOTIFY_WEBHOOK_DURATION_SECONDS,
+};
+use crate::utils::request_id::REQUEST_ID_HEADER;
 
 pub mod mqtt_tasks;
 pub mod task_manager;
@@ -16,8 +23,8 @@ pub enum TaskType {
 impl Display for TaskType {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
-            TaskType::LightSwitchTask => write!(f, "{}", "LIGHT_SWITCH_TASK"),
-            TaskType::LightStatusTask => write!(f, "{}", "LIGHT_STATUS_TASK"),
+            TaskType::LightSwitchTask => write!(f, "LIGHT_SWITCH_TASK"),
+            TaskType::LightStatusTask => write!(f, "LIGHT_STATUS_TASK"),
         }
     }
 }
@@ -56,11 +63,37 @@ impl NextRetryDuration {
     }
 }
 
-async fn handle_error(job: &Job) {
+/// 通知由接口下发的指令触发时，回调请求带上原请求的 `X-Request-Id`
+fn with_request_id(builder: RequestBuilder, job: &Job) -> RequestBuilder {
+    match &job.request_id {
+        Some(request_id) => builder.header(REQUEST_ID_HEADER, request_id),
+        None => builder,
+    }
+}
+
+/// 记录一次 webhook 请求的耗时，请求失败时同样记录
+fn observe_webhook_duration(job: &Job, started: Instant) {
+    histogram!(NOTIFY_WEBHOOK_DURATION_SECONDS, "type" => job.job_type.clone())
+        .record(started.elapsed().as_secs_f64());
+}
+
+/// 记录一次发送结果，供管理接口查看重试历史
+async fn record_attempt(job: &Job, is_success: bool, status_code: Option<u16>, error: String) {
+    if let Err(err) =
+        UVLampMqttNotifyJobAttempt::create(job.id, is_success, status_code, error).await
+    {
+        error!("Failed to record attempt of job {}: {}", job.id, err);
+    }
+}
+
+async fn handle_error(job: &Job, status_code: Option<u16>, error: String) {
+    record_attempt(job, false, status_code, error).await;
+
     let retry_count = job.retry_count + 1;
     let option_seconds = NextRetryDuration::from_retry_count(retry_count);
 
     if let Some(seconds) = option_seconds {
+        counter!(NOTIFY_JOB_RETRIES_TOTAL, "type" => job.job_type.clone()).increment(1);
         let current_timestamp = Utc::now().timestamp() as u64;
         let next_timestamp  = current_timestamp + seconds.as_seconds();
 
@@ -72,13 +105,21 @@ async fn handle_error(job: &Job) {
         ).await {
             error!("Failed to update retry count: {}", err);
         };
-    } else if let Err(err) = UVLampMqttNotifyJob::update_failed(job.id).await {
-        error!("Update job failed: {}", err);
+    } else {
+        counter!(NOTIFY_JOBS_TOTAL, "type" => job.job_type.clone(), "outcome" => "failed")
+            .increment(1);
+        if let Err(err) = UVLampMqttNotifyJob::update_failed(job.id).await {
+            error!("Update job failed: {}", err);
+        }
     }
 }
 
-async fn handle_received_response(job: &&Job, response: Response) {
+async fn handle_received_response(job: &Job, response: Response) {
+    let status_code = response.status().as_u16();
     if response.status().is_success() {
+        record_attempt(job, true, Some(status_code), String::new()).await;
+        counter!(NOTIFY_JOBS_TOTAL, "type" => job.job_type.clone(), "outcome" => "success")
+            .increment(1);
         let result = UVLampMqttNotifyJob::update_success(job.id).await;
         match result {
             Ok(_) => info!("Job notify task has completed successfully!"),
@@ -89,6 +130,11 @@ async fn handle_received_response(job: &&Job, response: Response) {
             "Request endpoint failed, status is {}",
             response.status().as_str()
         );
-        handle_error(&job).await;
+        handle_error(
+            job,
+            Some(status_code),
+            format!("Endpoint responded with status {}", status_code),
+        )
+        .await;
     }
 }
\ No newline at end of file
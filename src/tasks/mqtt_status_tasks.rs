@@ -1,16 +1,19 @@
 use std::fmt::{Display, Formatter};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use chrono::Local;
 use futures::future::BoxFuture;
 use futures::stream::FuturesUnordered;
 use futures::StreamExt;
 use reqwest::{Client};
-use serde::{Deserialize, Serialize};
+use serde::Serialize;
 use tokio::sync::{Notify, Semaphore};
 use tracing::{debug, error, info};
+use crate::protocol::network::NetworkReport;
 use crate::repositories::uv_lamp_mqtt_notify_job::{Job, UVLampMqttNotifyJob};
-use crate::tasks::{handle_error, handle_received_response, TaskType};
+use crate::tasks::{
+    handle_error, handle_received_response, observe_webhook_duration, with_request_id, TaskType,
+};
 
 #[derive(Debug, Clone)]
 struct Config {
@@ -55,26 +58,6 @@ impl Config {
     }
 }
 
-#[derive(Debug, Deserialize)]
-struct Payload {
-    // 下面这些字段因为暂时不用，所以注释
-
-    // 查询请求的随机数
-    // id: u32,
-
-    // 0 表示成功
-    // code: i32,
-
-    // 客户端 IP 地址
-    // ip: String,
-
-    // 信号质量（1-5，最大值为 5）
-    // rssi: i32,
-
-    // 时间戳（Format: YYYY-MM-DD hh:mm:ss)
-    ts: String,
-}
-
 #[derive(Debug, Serialize)]
 struct NotifyBody {
     // 设备编号
@@ -86,7 +69,7 @@ struct NotifyBody {
 }
 
 impl NotifyBody {
-    fn from_payload(payload: Payload, device_number: String) -> Self {
+    fn from_payload(payload: NetworkReport, device_number: String) -> Self {
         NotifyBody {
             device_number,
             is_online: true,
@@ -159,19 +142,24 @@ async fn send_requests(jobs: Vec<Job>, config: &Config) {
         }));
     }
 
-    while let Some(_) = futures.next().await {}
+    while futures.next().await.is_some() {}
 }
 
 async fn send_request(job: &Job, semaphore: &Semaphore, client: &Client, config: Config) {
     let _permit = semaphore.acquire().await;
-    let body = build_notify_body(&job);
+    let body = build_notify_body(job);
     debug!("Sending notification: {:?}", body);
-    let request_result = client.post(&config.notify_url).json(&body).send().await;
+    let started = Instant::now();
+    let request_result = with_request_id(client.post(&config.notify_url), job)
+        .json(&body)
+        .send()
+        .await;
+    observe_webhook_duration(job, started);
     if let Err(e) = request_result {
         error!("Failed to send notification: {}", e);
-        handle_error(&job).await;
+        handle_error(job, None, e.to_string()).await;
     } else if let Ok(response) = request_result {
-        handle_received_response(&job, response).await;
+        handle_received_response(job, response).await;
     }
 }
 
@@ -193,10 +181,10 @@ fn build_notify_body(job: &Job) -> NotifyBody {
     }
 }
 
-fn notify_contents_2_payload(notify_contents: &String, device_number: &str) -> NotifyBody {
-    let payload: Payload = serde_json::from_str(&notify_contents).map_err(|_| {
+fn notify_contents_2_payload(notify_contents: &str, device_number: &str) -> NotifyBody {
+    let payload: NetworkReport = serde_json::from_str(notify_contents).map_err(|_| {
         error!("Failed to parse notify contents: {}", notify_contents);
-    }).ok().expect("Failed to parse notify contents!");
+    }).expect("Failed to parse notify contents!");
 
     NotifyBody::from_payload(payload, device_number.to_string())
 }
\ No newline at end of file
@@ -1,162 +1,31 @@
+use crate::protocol::common::{LampStatus, Reason};
+use crate::protocol::status::StatusReport;
 use crate::repositories::uv_lamp_mqtt_notify_job::{Job, UVLampMqttNotifyJob};
 use futures::future::BoxFuture;
 use futures::stream::FuturesUnordered;
 use futures::StreamExt;
 use reqwest::{Client};
-use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde::Serialize;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::sync::{Notify, Semaphore};
 use tracing::{debug, error, info};
-use crate::tasks::{handle_error, handle_received_response, TaskType};
-
-#[derive(Debug)]
-enum LampStatus {
-    Free,
-    Off,
-    Check,
-    Running,
-}
-
-impl LampStatus {
-    fn as_int(&self) -> u8 {
-        match self {
-            LampStatus::Free => 0,
-            LampStatus::Off => 1,
-            LampStatus::Check => 2,
-            LampStatus::Running => 3,
-        }
-    }
-}
-
-impl Serialize for LampStatus {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: Serializer,
-    {
-        serializer.serialize_u8(self.as_int())
-    }
-}
-
-impl<'de> Deserialize<'de> for LampStatus {
-    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
-    where
-        D: Deserializer<'de>,
-    {
-        let value = u8::deserialize(deserializer)?;
-        match value {
-            0 => Ok(LampStatus::Free),
-            1 => Ok(LampStatus::Off),
-            2 => Ok(LampStatus::Check),
-            3 => Ok(LampStatus::Running),
-            _ => Err(serde::de::Error::custom("Invalid value for LampStatus")),
-        }
-    }
-}
-
-#[derive(Debug)]
-enum Reason {
-    // 状态更新
-    StatusModified,
-    // 定时打开
-    TimedOpen,
-    // 定时关闭
-    TimedOff,
-    // 平台打开
-    PlatformOpen,
-    // 平台关闭
-    PlatformOff,
-    // 发生红外报警
-    InfraredAlarmActivated,
-    // 红外报警解除
-    InfraredAlarmDeactivated,
-    // 检测正常
-    DetectionNormal,
-    // 非法灯管
-    IllegalLamp,
-}
-
-impl Serialize for Reason {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: Serializer,
-    {
-        serializer.serialize_u8(self.as_int())
-    }
-}
-
-impl<'de> Deserialize<'de> for Reason {
-    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
-    where
-        D: Deserializer<'de>,
-    {
-        let value = u8::deserialize(deserializer)?;
-        match value {
-            1 => Ok(Reason::StatusModified),
-            2 => Ok(Reason::TimedOpen),
-            3 => Ok(Reason::TimedOff),
-            4 => Ok(Reason::PlatformOpen),
-            5 => Ok(Reason::PlatformOff),
-            6 => Ok(Reason::InfraredAlarmActivated),
-            7 => Ok(Reason::InfraredAlarmDeactivated),
-            8 => Ok(Reason::DetectionNormal),
-            9 => Ok(Reason::IllegalLamp),
-            _ => Err(serde::de::Error::custom("Invalid value for Reason")),
-        }
-    }
-}
-
-impl Reason {
-    fn as_int(&self) -> u8 {
-        match self {
-            Reason::StatusModified => 1,
-            Reason::TimedOpen => 2,
-            Reason::TimedOff => 3,
-            Reason::PlatformOpen => 4,
-            Reason::PlatformOff => 5,
-            Reason::InfraredAlarmActivated => 6,
-            Reason::InfraredAlarmDeactivated => 7,
-            Reason::DetectionNormal => 8,
-            Reason::IllegalLamp => 9,
-        }
-    }
-}
-
-#[derive(Deserialize, Debug)]
-struct Payload {
-    // 灯的状态
-    #[serde(rename = "s")]
-    status: LampStatus,
-
-    // 紫外线强度，最大为 200
-    #[serde(rename = "u")]
-    strength: i8,
-
-    // 消毒开启时间，单位分钟
-    #[serde(rename = "d")]
-    duration: i32,
-
-    // 时间: YYYY-mm-dd HH:mm:ss
-    #[serde(rename = "ts")]
-    timestamp: String,
-
-    // 切换到当前状态的原因
-    #[serde(rename = "c")]
-    reason: Reason,
-}
+use crate::tasks::{
+    handle_error, handle_received_response, observe_webhook_duration, with_request_id, TaskType,
+};
 
 #[derive(Serialize, Debug)]
 struct NotifyBody {
     status: LampStatus,
     device_number: String,
-    strength: i8,
+    strength: u8,
     duration: i32,
     timestamp: String,
     reason: Reason,
 }
 
 impl NotifyBody {
-    fn from_payload(payload: Payload, device_number: String) -> Self {
+    fn from_payload(payload: StatusReport, device_number: String) -> Self {
         NotifyBody {
             device_number,
             status: payload.status,
@@ -208,7 +77,7 @@ async fn send_requests(jobs: Vec<Job>) {
     let timeout_seconds = std::env::var("UV_LAMP_MQTT_TASK_TIMEOUT")
         .unwrap_or_else(|_| "5".to_string())
         .parse()
-        .unwrap_or_else(|_| 5);
+        .unwrap_or(5);
     let result = Client::builder()
         .timeout(Duration::from_secs(timeout_seconds))
         .build();
@@ -228,7 +97,7 @@ async fn send_requests(jobs: Vec<Job>) {
                 }));
             }
 
-            while let Some(_) = futures.next().await {}
+            while futures.next().await.is_some() {}
         }
         Err(_) => error!("Failed to create client"),
     }
@@ -241,12 +110,14 @@ async fn send_request(job: &Job, semaphore: &Arc<Semaphore>, client: &Client) {
         Ok(url) => {
             let body = notify_contents_2_payload(&job.notify_contents, &job.device_number);
             debug!("Sending notification: {:?}", body);
-            let request_result = client.post(url).json(&body).send().await;
+            let started = Instant::now();
+            let request_result = with_request_id(client.post(url), job).json(&body).send().await;
+            observe_webhook_duration(job, started);
             match request_result {
-                Ok(response) => handle_received_response(&job, response).await,
+                Ok(response) => handle_received_response(job, response).await,
                 Err(err) => {
                     error!("Request endpoint failed: {}", err);
-                    handle_error(&job).await
+                    handle_error(job, None, err.to_string()).await
                 }
             }
         }
@@ -254,10 +125,10 @@ async fn send_request(job: &Job, semaphore: &Arc<Semaphore>, client: &Client) {
     }
 }
 
-fn notify_contents_2_payload(notify_contents: &String, device_number: &String) -> NotifyBody {
-    let payload: Payload = serde_json::from_str(&notify_contents).map_err(|_| {
+fn notify_contents_2_payload(notify_contents: &str, device_number: &str) -> NotifyBody {
+    let payload: StatusReport = serde_json::from_str(notify_contents).map_err(|_| {
         error!("Failed to parse notify contents!");
-    }).ok().expect("Failed to parse notify contents!");
+    }).expect("Failed to parse notify contents!");
 
-    NotifyBody::from_payload(payload, device_number.clone())
+    NotifyBody::from_payload(payload, device_number.to_string())
 }
\ No newline at end of file
@@ -1,13 +1,16 @@
 use futures::future::BoxFuture;
+use once_cell::sync::OnceCell;
 use std::future::Future;
 use std::sync::Arc;
 use tokio::sync::{Mutex, Notify};
+use tokio::task::JoinHandle;
 
 type TaskLogic = Arc<dyn Fn(Arc<Notify>) -> BoxFuture<'static, ()> + Send + Sync>;
 
 pub struct TaskManager {
     notify: Arc<Notify>,
     tasks: Arc<Mutex<Vec<TaskLogic>>>,
+    handles: Mutex<Vec<JoinHandle<()>>>,
 }
 
 impl TaskManager {
@@ -15,6 +18,7 @@ impl TaskManager {
         TaskManager {
             notify: notify.clone(),
             tasks: Arc::new(Mutex::new(Vec::new())),
+            handles: Mutex::new(Vec::new()),
         }
     }
 
@@ -31,12 +35,36 @@ impl TaskManager {
 
     pub async fn start_tasks(&self) {
         let tasks = self.tasks.lock().await.clone();
+        let mut handles = self.handles.lock().await;
         for task in tasks {
             let task = task.clone();
             let notify_clone = self.notify.clone();
-            tokio::spawn(async move {
+            handles.push(tokio::spawn(async move {
                 (task)(notify_clone).await;
-            });
+            }));
         }
     }
+
+    /// 返回 (仍在运行的任务数, 已注册的任务数)，任务退出或 panic 后不再计入运行数
+    pub async fn running_tasks(&self) -> (usize, usize) {
+        let registered = self.tasks.lock().await.len();
+        let running = self
+            .handles
+            .lock()
+            .await
+            .iter()
+            .filter(|handle| !handle.is_finished())
+            .count();
+        (running, registered)
+    }
+}
+
+static TASK_MANAGER: OnceCell<Arc<TaskManager>> = OnceCell::new();
+
+pub fn init_task_manager(manager: TaskManager) -> Arc<TaskManager> {
+    TASK_MANAGER.get_or_init(|| Arc::new(manager)).clone()
+}
+
+pub fn instance() -> Option<Arc<TaskManager>> {
+    TASK_MANAGER.get().cloned()
 }
@@ -0,0 +1,219 @@
+use crate::utils::error::AppError;
+use once_cell::sync::OnceCell;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// 单台设备的限流和冲突检测配置，`max_commands` 为 0 时不限流
+#[derive(Debug, Clone, Copy)]
+pub struct GuardConfig {
+    pub max_commands: usize,
+    pub window: Duration,
+    // 超过该时间仍未确认的指令视为丢失，不再阻止相反的指令
+    pub pending_timeout: Duration,
+}
+
+impl GuardConfig {
+    fn from_env() -> Self {
+        let read = |key: &str, default: u64| {
+            std::env::var(key)
+                .ok()
+                .and_then(|value| value.parse::<u64>().ok())
+                .unwrap_or(default)
+        };
+        GuardConfig {
+            max_commands: read("UV_LAMP_TURN_RATE_LIMIT", 3) as usize,
+            window: Duration::from_secs(read("UV_LAMP_TURN_RATE_WINDOW", 10)),
+            pending_timeout: Duration::from_secs(read("UV_LAMP_TURN_PENDING_TIMEOUT", 10)),
+        }
+    }
+}
+
+struct PendingCommand {
+    message_id: String,
+    status: bool,
+    sent_at: Instant,
+}
+
+#[derive(Default)]
+struct DeviceCommands {
+    // 窗口内已下发指令的时间，最早的在前
+    sent: VecDeque<Instant>,
+    pending: Vec<PendingCommand>,
+}
+
+/// 下发开关指令前的检查：同一设备在窗口内的指令数不能超过上限，
+/// 存在未确认的相反指令时拒绝下发，避免灯管被频繁开关
+pub struct CommandGuard {
+    config: GuardConfig,
+    devices: Mutex<HashMap<String, DeviceCommands>>,
+}
+
+impl CommandGuard {
+    pub fn new(config: GuardConfig) -> Self {
+        CommandGuard {
+            config,
+            devices: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 检查通过后登记指令，下发失败时需要调用 [`CommandGuard::release`]
+    pub fn acquire(
+        &self,
+        device_number: &str,
+        message_id: &str,
+        status: bool,
+    ) -> Result<(), AppError> {
+        self.acquire_at(device_number, message_id, status, Instant::now())
+    }
+
+    fn acquire_at(
+        &self,
+        device_number: &str,
+        message_id: &str,
+        status: bool,
+        now: Instant,
+    ) -> Result<(), AppError> {
+        let mut devices = self.devices.lock().expect("command guard poisoned");
+        let commands = devices.entry(device_number.to_string()).or_default();
+
+        let pending_timeout = self.config.pending_timeout;
+        commands
+            .pending
+            .retain(|pending| now.duration_since(pending.sent_at) < pending_timeout);
+        if let Some(pending) = commands.pending.iter().find(|pending| pending.status != status) {
+            return Err(AppError::CommandConflict(format!(
+                "Device {} has an unacknowledged turn {} command {}",
+                device_number,
+                if pending.status { "on" } else { "off" },
+                pending.message_id
+            )));
+        }
+
+        if self.config.max_commands > 0 {
+            let window = self.config.window;
+            while let Some(sent_at) = commands.sent.front() {
+                if now.duration_since(*sent_at) < window {
+                    break;
+                }
+                commands.sent.pop_front();
+            }
+            if commands.sent.len() >= self.config.max_commands {
+                return Err(AppError::RateLimited(format!(
+                    "Device {} accepts at most {} commands per {} seconds",
+                    device_number,
+                    self.config.max_commands,
+                    window.as_secs()
+                )));
+            }
+            commands.sent.push_back(now);
+        }
+
+        commands.pending.push(PendingCommand {
+            message_id: message_id.to_string(),
+            status,
+            sent_at: now,
+        });
+        Ok(())
+    }
+
+    /// 设备确认指令后移除对应的未确认记录
+    pub fn acknowledge(&self, device_number: &str, message_id: &str) {
+        let mut devices = self.devices.lock().expect("command guard poisoned");
+        if let Some(commands) = devices.get_mut(device_number) {
+            commands
+                .pending
+                .retain(|pending| pending.message_id != message_id);
+        }
+    }
+
+    /// 指令没有发出时撤销登记，不计入限流次数
+    pub fn release(&self, device_number: &str, message_id: &str) {
+        let mut devices = self.devices.lock().expect("command guard poisoned");
+        if let Some(commands) = devices.get_mut(device_number) {
+            if let Some(index) = commands
+                .pending
+                .iter()
+                .position(|pending| pending.message_id == message_id)
+            {
+                let pending = commands.pending.remove(index);
+                let position = commands
+                    .sent
+                    .iter()
+                    .rposition(|sent_at| *sent_at == pending.sent_at);
+                if let Some(position) = position {
+                    commands.sent.remove(position);
+                }
+            }
+        }
+    }
+}
+
+static COMMAND_GUARD: OnceCell<CommandGuard> = OnceCell::new();
+
+pub fn get_command_guard() -> &'static CommandGuard {
+    COMMAND_GUARD.get_or_init(|| CommandGuard::new(GuardConfig::from_env()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{CommandGuard, GuardConfig};
+    use crate::utils::error::AppError;
+    use std::time::{Duration, Instant};
+
+    fn guard(max_commands: usize) -> CommandGuard {
+        CommandGuard::new(GuardConfig {
+            max_commands,
+            window: Duration::from_secs(10),
+            pending_timeout: Duration::from_secs(5),
+        })
+    }
+
+    #[test]
+    fn test_rate_limit() {
+        let guard = guard(2);
+        let now = Instant::now();
+        assert!(guard.acquire_at("A", "1", true, now).is_ok());
+        guard.acknowledge("A", "1");
+        assert!(guard.acquire_at("A", "2", true, now).is_ok());
+        assert!(matches!(
+            guard.acquire_at("A", "3", true, now),
+            Err(AppError::RateLimited(_))
+        ));
+        // 其他设备不受影响
+        assert!(guard.acquire_at("B", "1", true, now).is_ok());
+        // 窗口过后恢复
+        assert!(guard
+            .acquire_at("A", "4", true, now + Duration::from_secs(10))
+            .is_ok());
+    }
+
+    #[test]
+    fn test_conflicting_command() {
+        let guard = guard(0);
+        let now = Instant::now();
+        assert!(guard.acquire_at("A", "1", true, now).is_ok());
+        assert!(matches!(
+            guard.acquire_at("A", "2", false, now),
+            Err(AppError::CommandConflict(_))
+        ));
+
+        // 确认后可以下发相反的指令
+        guard.acknowledge("A", "1");
+        assert!(guard.acquire_at("A", "3", false, now).is_ok());
+        // 未确认的指令超时后不再阻止
+        assert!(guard
+            .acquire_at("A", "4", true, now + Duration::from_secs(5))
+            .is_ok());
+    }
+
+    #[test]
+    fn test_release() {
+        let guard = guard(2);
+        let now = Instant::now();
+        assert!(guard.acquire_at("A", "1", true, now).is_ok());
+        guard.release("A", "1");
+        assert!(guard.acquire_at("A", "2", false, now).is_ok());
+        assert!(guard.acquire_at("A", "3", false, now).is_ok());
+    }
+}
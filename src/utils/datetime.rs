@@ -0,0 +1,54 @@
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+
+pub fn format_datetime(time: &DateTime<Utc>, timezone: &Tz) -> String {
+    time.with_timezone(timezone)
+        .format("%Y-%m-%d %H:%M:%S")
+        .to_string()
+}
+
+/// 将秒级时间戳格式化为本地时间，时间戳无效时返回 `None`
+pub fn format_timestamp(timestamp: u64, timezone: &Tz) -> Option<String> {
+    DateTime::from_timestamp(timestamp as i64, 0).map(|time| format_datetime(&time, timezone))
+}
+
+/// 解析 RFC 3339 时间，或按 `timezone` 解释的 `YYYY-MM-DD HH:MM:SS`、`YYYY-MM-DD` 本地时间
+pub fn parse_datetime(value: &str, timezone: &Tz) -> Result<DateTime<Utc>, anyhow::Error> {
+    let value = value.trim();
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Ok(time.with_timezone(&Utc));
+    }
+    let naive = NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S")
+        .or_else(|_| {
+            NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .map(|date| date.and_hms_opt(0, 0, 0).unwrap_or_default())
+        })
+        .map_err(|_| anyhow::anyhow!("Invalid datetime '{}'", value))?;
+    timezone
+        .from_local_datetime(&naive)
+        .earliest()
+        .map(|time| time.with_timezone(&Utc))
+        .ok_or_else(|| anyhow::anyhow!("Datetime '{}' does not exist in {}", value, timezone))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{format_datetime, parse_datetime};
+    use chrono_tz::Tz;
+
+    #[test]
+    fn test_parse_datetime() {
+        let timezone = Tz::Asia__Shanghai;
+        let time = parse_datetime("2024-05-01 08:30:00", &timezone).unwrap();
+        assert_eq!(time.to_rfc3339(), "2024-05-01T00:30:00+00:00");
+        assert_eq!(format_datetime(&time, &timezone), "2024-05-01 08:30:00");
+
+        let time = parse_datetime("2024-05-01", &timezone).unwrap();
+        assert_eq!(time.to_rfc3339(), "2024-04-30T16:00:00+00:00");
+
+        let time = parse_datetime("2024-05-01T08:30:00Z", &timezone).unwrap();
+        assert_eq!(time.to_rfc3339(), "2024-05-01T08:30:00+00:00");
+
+        assert!(parse_datetime("yesterday", &timezone).is_err());
+    }
+}
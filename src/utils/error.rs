@@ -3,33 +3,215 @@ use axum::{
     response::{IntoResponse, Response},
     Json,
 };
-use serde_json::json;
+use serde::Serialize;
 use std::fmt;
+use tracing::error;
+use utoipa::ToSchema;
+use validator::ValidationErrors;
 
+/// 接口错误，每种错误对应固定的 `code` 和 HTTP 状态码，客户端应根据 `code` 判断错误类型
 #[derive(Debug)]
-pub struct AppError {
-    message: String,
+pub enum AppError {
+    // 参数格式错误
+    BadRequest(String),
+    // 参数校验失败，附带每个字段的错误
+    Validation(Vec<FieldError>),
+    Unauthorized(String),
+    Forbidden(String),
+    NotFound(String),
+    // 与资源当前状态冲突，如重复创建、状态不允许的操作
+    Conflict(String),
+    DeviceOffline(String),
+    MqttUnavailable(String),
+    // 在等待时间内没有收到设备回复
+    ReplyTimeout(String),
+    // 设备指令过于频繁
+    RateLimited(String),
+    // 与设备尚未确认的指令相反
+    CommandConflict(String),
+    Database(String),
+    Internal(String),
+}
+
+/// 单个字段的校验错误，`code` 为 validator 的规则名，如 `range`、`length`
+#[derive(Debug, Serialize, ToSchema)]
+pub struct FieldError {
+    pub field: String,
+    pub code: String,
+    pub message: Option<String>,
+}
+
+/// 错误响应体，与 `ApiResponse` 结构一致，`data` 始终为空
+#[derive(Serialize, ToSchema)]
+pub struct ErrorResponse {
+    pub code: i32,
+    pub message: String,
+    #[schema(value_type = Option<Object>)]
+    pub data: Option<()>,
+    /// 参数校验失败时每个字段的错误
+    pub details: Option<Vec<FieldError>>,
 }
 
 impl AppError {
-    pub fn new(message: String) -> AppError {
-        AppError { message }
+    pub fn code(&self) -> i32 {
+        match self {
+            AppError::BadRequest(_) => 1000,
+            AppError::Validation(_) => 1001,
+            AppError::Unauthorized(_) => 1002,
+            AppError::Forbidden(_) => 1003,
+            AppError::NotFound(_) => 1004,
+            AppError::Conflict(_) => 1005,
+            AppError::DeviceOffline(_) => 2001,
+            AppError::MqttUnavailable(_) => 2002,
+            AppError::ReplyTimeout(_) => 2003,
+            AppError::RateLimited(_) => 2004,
+            AppError::CommandConflict(_) => 2005,
+            AppError::Database(_) => 3001,
+            AppError::Internal(_) => 5000,
+        }
+    }
+
+    pub fn status(&self) -> StatusCode {
+        match self {
+            AppError::BadRequest(_) | AppError::Validation(_) => StatusCode::BAD_REQUEST,
+            AppError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            AppError::Forbidden(_) => StatusCode::FORBIDDEN,
+            AppError::NotFound(_) => StatusCode::NOT_FOUND,
+            AppError::Conflict(_) | AppError::DeviceOffline(_) | AppError::CommandConflict(_) => {
+                StatusCode::CONFLICT
+            }
+            AppError::MqttUnavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+            AppError::ReplyTimeout(_) => StatusCode::GATEWAY_TIMEOUT,
+            AppError::RateLimited(_) => StatusCode::TOO_MANY_REQUESTS,
+            AppError::Database(_) | AppError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
     }
 }
 
 impl fmt::Display for AppError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.message)
+        match self {
+            AppError::Validation(errors) => {
+                let fields: Vec<&str> = errors.iter().map(|error| error.field.as_str()).collect();
+                write!(f, "Invalid parameters: {}", fields.join(", "))
+            }
+            AppError::BadRequest(message)
+            | AppError::Unauthorized(message)
+            | AppError::Forbidden(message)
+            | AppError::NotFound(message)
+            | AppError::Conflict(message)
+            | AppError::DeviceOffline(message)
+            | AppError::MqttUnavailable(message)
+            | AppError::ReplyTimeout(message)
+            | AppError::RateLimited(message)
+            | AppError::CommandConflict(message)
+            | AppError::Database(message)
+            | AppError::Internal(message) => write!(f, "{}", message),
+        }
     }
 }
 
 impl std::error::Error for AppError {}
 
+impl From<ValidationErrors> for AppError {
+    fn from(errors: ValidationErrors) -> Self {
+        let mut field_errors: Vec<FieldError> = errors
+            .field_errors()
+            .into_iter()
+            .flat_map(|(field, errors)| {
+                errors.iter().map(move |error| FieldError {
+                    field: field.to_string(),
+                    code: error.code.to_string(),
+                    message: error.message.as_ref().map(|message| message.to_string()),
+                })
+            })
+            .collect();
+        field_errors.sort_by(|a, b| a.field.cmp(&b.field));
+        AppError::Validation(field_errors)
+    }
+}
+
+/// 服务层返回的错误，如果本身是 `AppError` 则原样返回，数据库错误归为 `Database`，其余为 `Internal`
+impl From<anyhow::Error> for AppError {
+    fn from(error: anyhow::Error) -> Self {
+        let error = match error.downcast::<AppError>() {
+            Ok(app_error) => return app_error,
+            Err(error) => error,
+        };
+        match error.downcast_ref::<sqlx::Error>() {
+            Some(sqlx::Error::RowNotFound) => AppError::NotFound("Record not found".to_string()),
+            Some(e) => AppError::Database(e.to_string()),
+            None => AppError::Internal(error.to_string()),
+        }
+    }
+}
+
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
-        let body = Json(json!({
-            "message": self.message,
-        }));
-        (StatusCode::INTERNAL_SERVER_ERROR, body).into_response()
+        let status = self.status();
+        if status.is_server_error() {
+            error!("Request failed with code {}: {}", self.code(), self);
+        }
+        let body = ErrorResponse {
+            code: self.code(),
+            message: self.to_string(),
+            data: None,
+            details: match self {
+                AppError::Validation(errors) => Some(errors),
+                _ => None,
+            },
+        };
+        (status, Json(body)).into_response()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::AppError;
+    use axum::http::StatusCode;
+    use validator::Validate;
+
+    #[derive(Validate)]
+    struct Params {
+        #[validate(range(min = 1, max = 10))]
+        duration: i32,
+        #[validate(length(min = 1))]
+        name: String,
+    }
+
+    #[test]
+    fn test_from_validation_errors() {
+        let params = Params {
+            duration: 0,
+            name: String::new(),
+        };
+        let error = AppError::from(params.validate().unwrap_err());
+        assert_eq!(error.code(), 1001);
+        assert_eq!(error.status(), StatusCode::BAD_REQUEST);
+        match error {
+            AppError::Validation(errors) => {
+                let fields: Vec<(&str, &str)> = errors
+                    .iter()
+                    .map(|error| (error.field.as_str(), error.code.as_str()))
+                    .collect();
+                assert_eq!(fields, vec![("duration", "range"), ("name", "length")]);
+            }
+            other => panic!("unexpected error: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_from_anyhow_error() {
+        let error = AppError::from(anyhow::Error::from(AppError::NotFound("gone".to_string())));
+        assert_eq!(error.code(), 1004);
+        assert_eq!(error.status(), StatusCode::NOT_FOUND);
+
+        let error = AppError::from(anyhow::Error::from(sqlx::Error::PoolTimedOut));
+        assert_eq!(error.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(error.code(), 3001);
+
+        let error = AppError::from(anyhow::anyhow!("boom"));
+        assert_eq!(error.code(), 5000);
+        assert_eq!(error.to_string(), "boom");
     }
 }
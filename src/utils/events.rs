@@ -0,0 +1,175 @@
+use crate::protocol::common::{LampStatus, Reason};
+use once_cell::sync::OnceCell;
+use serde::Serialize;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::broadcast;
+use tracing::debug;
+use utoipa::ToSchema;
+
+const DEFAULT_CAPACITY: usize = 1024;
+
+/// 推送给实时订阅方的设备事件，`timestamp` 为秒级时间戳
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(tag = "type", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum LampEvent {
+    /// 设备上报开关灯状态
+    StatusChanged {
+        device_number: String,
+        /// 灯状态: 0-空闲;1-关闭;2-检测;3-运行
+        #[schema(value_type = u8)]
+        status: LampStatus,
+        /// 切换到当前状态的原因
+        #[schema(value_type = Option<u8>)]
+        reason: Option<Reason>,
+        timestamp: u64,
+    },
+    Online {
+        device_number: String,
+        timestamp: u64,
+    },
+    Offline {
+        device_number: String,
+        timestamp: u64,
+    },
+    /// 红外报警触发或解除
+    InfraredAlarm {
+        device_number: String,
+        activated: bool,
+        timestamp: u64,
+    },
+}
+
+impl LampEvent {
+    pub fn status_changed(device_number: String, status: LampStatus, reason: Option<Reason>) -> Self {
+        LampEvent::StatusChanged {
+            device_number,
+            status,
+            reason,
+            timestamp: now(),
+        }
+    }
+
+    pub fn online(device_number: String) -> Self {
+        LampEvent::Online {
+            device_number,
+            timestamp: now(),
+        }
+    }
+
+    pub fn offline(device_number: String) -> Self {
+        LampEvent::Offline {
+            device_number,
+            timestamp: now(),
+        }
+    }
+
+    pub fn infrared_alarm(device_number: String, activated: bool) -> Self {
+        LampEvent::InfraredAlarm {
+            device_number,
+            activated,
+            timestamp: now(),
+        }
+    }
+
+    pub fn device_number(&self) -> &str {
+        match self {
+            LampEvent::StatusChanged { device_number, .. }
+            | LampEvent::Online { device_number, .. }
+            | LampEvent::Offline { device_number, .. }
+            | LampEvent::InfraredAlarm { device_number, .. } => device_number,
+        }
+    }
+
+    /// SSE 的事件名
+    pub fn name(&self) -> &'static str {
+        match self {
+            LampEvent::StatusChanged { .. } => "status_changed",
+            LampEvent::Online { .. } => "online",
+            LampEvent::Offline { .. } => "offline",
+            LampEvent::InfraredAlarm { .. } => "infrared_alarm",
+        }
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Time went backwards")
+        .as_secs()
+}
+
+/// 进程内的设备事件总线，没有订阅方时事件直接丢弃，订阅方处理过慢时会丢失最早的事件
+pub struct EventBus {
+    sender: broadcast::Sender<LampEvent>,
+}
+
+impl EventBus {
+    fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity);
+        EventBus { sender }
+    }
+
+    pub fn publish(&self, event: LampEvent) {
+        debug!("Publish event {:?}", event);
+        // 没有订阅方时 send 返回错误，可以忽略
+        let _ = self.sender.send(event);
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<LampEvent> {
+        self.sender.subscribe()
+    }
+}
+
+static EVENT_BUS: OnceCell<EventBus> = OnceCell::new();
+
+pub fn get_event_bus() -> &'static EventBus {
+    EVENT_BUS.get_or_init(|| {
+        let capacity = std::env::var("UV_LAMP_EVENT_BUFFER_SIZE")
+            .ok()
+            .and_then(|value| value.parse::<usize>().ok())
+            .filter(|capacity| *capacity > 0)
+            .unwrap_or(DEFAULT_CAPACITY);
+        EventBus::new(capacity)
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::{EventBus, LampEvent};
+    use crate::protocol::common::{LampStatus, Reason};
+    use serde_json::json;
+
+    #[test]
+    fn test_serialize_event() {
+        let event = LampEvent::StatusChanged {
+            device_number: "867255071234567".to_string(),
+            status: LampStatus::Running,
+            reason: Some(Reason::PlatformOpen),
+            timestamp: 1_700_000_000,
+        };
+        assert_eq!(event.name(), "status_changed");
+        assert_eq!(
+            serde_json::to_value(&event).unwrap(),
+            json!({
+                "type": "STATUS_CHANGED",
+                "device_number": "867255071234567",
+                "status": 3,
+                "reason": 4,
+                "timestamp": 1_700_000_000,
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_publish_to_subscribers() {
+        let bus = EventBus::new(4);
+        // 没有订阅方时发布不会报错
+        bus.publish(LampEvent::online("A".to_string()));
+
+        let mut receiver = bus.subscribe();
+        bus.publish(LampEvent::offline("B".to_string()));
+        let event = receiver.recv().await.unwrap();
+        assert_eq!(event.device_number(), "B");
+        assert_eq!(event.name(), "offline");
+    }
+}
@@ -1,14 +1,14 @@
-use jsonwebtoken::{encode, EncodingKey, Header};
+use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
 use serde::{Deserialize, Serialize};
 use std::time::{SystemTime, UNIX_EPOCH};
 
 #[derive(Debug, Serialize, Deserialize)]
-struct Claims {
-    sub: String,
-    exp: usize,
+pub struct Claims {
+    pub sub: String,
+    pub exp: usize,
 }
 
-pub fn create_token(sub: &String, secret: &str) -> Result<String, anyhow::Error> {
+pub fn create_token(sub: &str, secret: &str) -> Result<String, anyhow::Error> {
     let expiration = SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .expect("valid timestamp")
@@ -16,7 +16,7 @@ pub fn create_token(sub: &String, secret: &str) -> Result<String, anyhow::Error>
         + 7 * 24 * 60 * 60;
 
     let claims = Claims {
-        sub: sub.clone(),
+        sub: sub.to_string(),
         exp: expiration as usize,
     };
 
@@ -32,10 +32,25 @@ pub fn create_token(sub: &String, secret: &str) -> Result<String, anyhow::Error>
     }
 }
 
+/// 校验签名和过期时间，通过后返回 token 中的声明
+pub fn verify_token(token: &str, secret: &str) -> Result<Claims, anyhow::Error> {
+    let result = decode::<Claims>(
+        token,
+        &DecodingKey::from_secret(secret.as_ref()),
+        &Validation::default(),
+    );
+
+    match result {
+        Ok(data) => Ok(data.claims),
+        Err(e) => Err(anyhow::anyhow!(e.to_string())),
+    }
+}
+
 #[cfg(test)]
 mod test {
 
-    use super::create_token;
+    use super::{create_token, verify_token, Claims};
+    use jsonwebtoken::{encode, EncodingKey, Header};
 
     #[test]
     fn test_create_token() {
@@ -45,4 +60,27 @@ mod test {
         dbg!(&token);
         assert_ne!(token, "");
     }
+
+    #[test]
+    fn test_verify_token() {
+        let token = create_token("test", "secret").unwrap();
+        let claims = verify_token(&token, "secret").unwrap();
+        assert_eq!(claims.sub, "test");
+        assert!(verify_token(&token, "other").is_err());
+    }
+
+    #[test]
+    fn test_verify_expired_token() {
+        let claims = Claims {
+            sub: "test".to_string(),
+            exp: 1,
+        };
+        let token = encode(
+            &Header::default(),
+            &claims,
+            &EncodingKey::from_secret("secret".as_ref()),
+        )
+        .unwrap();
+        assert!(verify_token(&token, "secret").is_err());
+    }
 }
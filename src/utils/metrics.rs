@@ -0,0 +1,65 @@
+use crate::utils::mqtt::get_device_manager;
+use crate::utils::mysql::MySql;
+use metrics::gauge;
+use metrics_exporter_prometheus::{Matcher, PrometheusBuilder, PrometheusHandle};
+use once_cell::sync::OnceCell;
+use tracing::error;
+
+pub const HTTP_REQUESTS_TOTAL: &str = "http_requests_total";
+pub const HTTP_REQUEST_DURATION_SECONDS: &str = "http_request_duration_seconds";
+pub const MQTT_MESSAGES_RECEIVED_TOTAL: &str = "mqtt_messages_received_total";
+pub const MQTT_PUBLISHES_TOTAL: &str = "mqtt_publishes_total";
+pub const NOTIFY_JOBS_TOTAL: &str = "notify_jobs_total";
+pub const NOTIFY_JOB_RETRIES_TOTAL: &str = "notify_job_retries_total";
+pub const NOTIFY_WEBHOOK_DURATION_SECONDS: &str = "notify_webhook_duration_seconds";
+pub const CRON_TASK_DURATION_SECONDS: &str = "cron_task_duration_seconds";
+pub const DEVICES: &str = "uv_lamp_devices";
+pub const DB_POOL_CONNECTIONS: &str = "db_pool_connections";
+pub const DB_POOL_MAX_CONNECTIONS: &str = "db_pool_max_connections";
+
+// 接口和 webhook 耗时的分桶，单位秒
+const REQUEST_BUCKETS: &[f64] = &[0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0];
+// 定时任务耗时的分桶，单位秒
+const TASK_BUCKETS: &[f64] = &[0.01, 0.1, 0.5, 1.0, 5.0, 10.0, 30.0, 60.0, 300.0];
+
+static PROMETHEUS_HANDLE: OnceCell<PrometheusHandle> = OnceCell::new();
+
+/// 安装全局的指标记录器，未安装时各处记录指标不产生任何效果
+pub fn init_metrics() -> Result<(), anyhow::Error> {
+    PROMETHEUS_HANDLE.get_or_try_init(|| {
+        PrometheusBuilder::new()
+            .set_buckets_for_metric(
+                Matcher::Full(CRON_TASK_DURATION_SECONDS.to_string()),
+                TASK_BUCKETS,
+            )?
+            .set_buckets_for_metric(Matcher::Suffix("_seconds".to_string()), REQUEST_BUCKETS)?
+            .install_recorder()
+            .map_err(anyhow::Error::from)
+    })?;
+    Ok(())
+}
+
+/// 输出 Prometheus 文本格式的指标，设备在线数和连接池等状态类指标在这里刷新
+pub async fn render() -> String {
+    let handle = match PROMETHEUS_HANDLE.get() {
+        Some(handle) => handle,
+        None => return String::new(),
+    };
+
+    let (online, offline) = get_device_manager().lock().await.count_by_status();
+    gauge!(DEVICES, "state" => "online").set(online as f64);
+    gauge!(DEVICES, "state" => "offline").set(offline as f64);
+
+    match MySql::get_instance().await {
+        Ok(mysql) => {
+            let size = mysql.pool.size();
+            let idle = mysql.pool.num_idle() as u32;
+            gauge!(DB_POOL_CONNECTIONS, "state" => "idle").set(idle as f64);
+            gauge!(DB_POOL_CONNECTIONS, "state" => "active").set(size.saturating_sub(idle) as f64);
+            gauge!(DB_POOL_MAX_CONNECTIONS).set(mysql.pool.options().get_max_connections() as f64);
+        }
+        Err(e) => error!("Failed to get database pool: {}", e),
+    }
+
+    handle.render()
+}
@@ -1,6 +1,15 @@
+pub mod command_guard;
 pub mod config;
+pub mod datetime;
 pub mod error;
+pub mod events;
 pub mod jwt;
+pub mod metrics;
 pub mod mqtt;
+pub mod mqtt_connection;
+pub mod mqtt_router;
+pub mod mqtt_tls;
 pub mod mysql;
 pub mod password;
+pub mod request_id;
+pub mod topic;
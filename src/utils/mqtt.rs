@@ -1,79 +1,227 @@
 use std::collections::HashMap;
+use crate::repositories::uv_lamp_mqtt_message::UVLampMqttMessage;
 use crate::repositories::uv_lamp_mqtt_notify_job::UVLampMqttNotifyJob;
 use crate::repositories::uv_lamp_mqtt_received_messages::UVLampMqttReceivedMessages;
+use crate::protocol;
+use crate::protocol::command::Reply;
+use crate::protocol::common::Reason;
+use crate::protocol::status::StatusChange;
 use crate::tasks::TaskType;
+use crate::utils::command_guard::get_command_guard;
+use crate::utils::events::{get_event_bus, LampEvent};
+use crate::utils::metrics::{MQTT_MESSAGES_RECEIVED_TOTAL, MQTT_PUBLISHES_TOTAL};
+use crate::utils::mqtt_connection::{Backoff, ConnectionStatus, ConnectionTracker};
+use crate::utils::mqtt_tls::TlsSettings;
+use crate::utils::request_id;
+use crate::utils::mqtt_router::{InboundMessage, TopicHandler, TopicRouter};
+use crate::utils::topic::{
+    get_topic_config, init_topic_config, TopicKind, CHANNEL_COMMAND_ACK, CHANNEL_NETWORK_REPORT,
+    CHANNEL_STATUS_REPORT,
+};
 use anyhow::anyhow;
+use futures::future::{self, BoxFuture};
+use futures::FutureExt;
+use metrics::counter;
 use once_cell::sync::OnceCell;
-use rumqttc::{AsyncClient, Event, Incoming, MqttOptions, Publish, QoS};
+use rumqttc::{
+    AsyncClient, Event, Incoming, MqttOptions, Publish, QoS, SubscribeFilter, SubscribeReasonCode,
+};
+use std::sync::atomic::{AtomicU64, Ordering};
+use serde_json::Value;
 use std::sync::Arc;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
-use tokio::sync::{mpsc, Mutex};
-use tracing::{debug, error, info};
+use tokio::sync::{mpsc, oneshot, Mutex};
+use tracing::{debug, error, info, info_span, warn, Instrument};
 
 pub struct MqttHandler {
     sender: mpsc::Sender<(String, String)>,
+    connection: Arc<ConnectionTracker>,
 }
 
-const SUBSCRIBE_TOPIC: [&str; 3] = [
-    "87855294541367dab3e244c2441c5f22/+/oc/c",
-    "87855294541367dab3e244c2441c5f22/+/up/c",
-    "87855294541367dab3e244c2441c5f22/+/nI/c",
-];
+/// 新增消息类型时在这里注册处理器，同一主题的处理器按注册顺序执行
+fn router() -> TopicRouter {
+    let topics = get_topic_config();
+    TopicRouter::new()
+        .route(topics.filter(CHANNEL_COMMAND_ACK), ReplyWaiterHandler)
+        .route(topics.filter(CHANNEL_STATUS_REPORT), ReplyWaiterHandler)
+        .route(topics.filter(CHANNEL_COMMAND_ACK), CommandAckHandler)
+        .route(topics.filter(CHANNEL_STATUS_REPORT), CommandAckHandler)
+        .route(topics.filter(CHANNEL_STATUS_REPORT), LightSwitchHandler)
+        .route(topics.filter(CHANNEL_NETWORK_REPORT), LightNetworkHandler)
+}
+
+/// 唤醒同步等待该回复的接口请求，没有 id 的主动上报直接跳过
+struct ReplyWaiterHandler;
+
+impl TopicHandler for ReplyWaiterHandler {
+    type Payload = Reply;
+
+    fn name(&self) -> &'static str {
+        "reply_waiter"
+    }
+
+    fn handle<'a>(
+        &'a self,
+        message: &'a InboundMessage,
+        payload: Reply,
+    ) -> BoxFuture<'a, ()> {
+        if let Some(id) = payload.id {
+            get_reply_waiters().resolve(&message.device_number, &id.to_string(), &message.payload);
+        }
+        future::ready(()).boxed()
+    }
+}
+
+struct CommandAckHandler;
+
+impl TopicHandler for CommandAckHandler {
+    type Payload = Reply;
 
-enum Message {
-    LightSwitchResponse(String),
-    LightNetworkResponse(String),
+    fn name(&self) -> &'static str {
+        "command_ack"
+    }
+
+    fn handle<'a>(
+        &'a self,
+        message: &'a InboundMessage,
+        payload: Reply,
+    ) -> BoxFuture<'a, ()> {
+        async move {
+            // 设备回复时会带上指令中的 id，据此确认对应的指令
+            let Some(id) = payload.id else {
+                return;
+            };
+            let device_number = &message.device_number;
+            let message_id = id.to_string();
+            get_command_guard().acknowledge(device_number, &message_id);
+            match UVLampMqttMessage::mark_acked(&message_id, device_number).await {
+                Ok(Some(latency)) => info!(
+                    "Command {} acked by device {} in {}ms, topic {}",
+                    message_id, device_number, latency, message.topic
+                ),
+                Ok(None) => debug!(
+                    "No pending command {} for device {}, topic {}",
+                    message_id, device_number, message.topic
+                ),
+                Err(e) => error!("An error occurred: {}", e),
+            }
+        }
+        .boxed()
+    }
 }
 
-trait MessageHandle {
-    async fn handle(&self, topic: &String, device_number: String, payload: String);
+struct LightSwitchHandler;
+
+impl LightSwitchHandler {
+    fn publish_events(device_number: &str, payload: &StatusChange) {
+        let bus = get_event_bus();
+        bus.publish(LampEvent::status_changed(
+            device_number.to_string(),
+            payload.status,
+            payload.reason,
+        ));
+        match payload.reason {
+            Some(Reason::InfraredAlarmActivated) => {
+                bus.publish(LampEvent::infrared_alarm(device_number.to_string(), true))
+            }
+            Some(Reason::InfraredAlarmDeactivated) => {
+                bus.publish(LampEvent::infrared_alarm(device_number.to_string(), false))
+            }
+            _ => {}
+        }
+    }
 }
 
-struct LightSwitchMessageHandler;
+impl TopicHandler for LightSwitchHandler {
+    type Payload = StatusChange;
 
-impl MessageHandle for LightSwitchMessageHandler {
-    async fn handle(&self, topic: &String, device_number: String, payload: String) {
-        let result = UVLampMqttNotifyJob::create(
-            device_number,
-            payload,
-            TaskType::LightSwitchTask.to_string(),
-        )
+    fn name(&self) -> &'static str {
+        "light_switch"
+    }
+
+    fn handle<'a>(
+        &'a self,
+        message: &'a InboundMessage,
+        payload: StatusChange,
+    ) -> BoxFuture<'a, ()> {
+        async move {
+            Self::publish_events(&message.device_number, &payload);
+            let result = UVLampMqttNotifyJob::create(
+                message.device_number.clone(),
+                message.payload.clone(),
+                TaskType::LightSwitchTask.to_string(),
+                request_id::current(),
+            )
             .await;
-        match result {
-            Ok(id) => info!(
-                "Created light switch notification job, id {}, topic {}",
-                id, topic
-            ),
-            Err(e) => error!("An error occurred: {}", e),
+            match result {
+                Ok(id) => info!(
+                    "Created light switch notification job, id {}, topic {}",
+                    id, message.topic
+                ),
+                Err(e) => error!("An error occurred: {}", e),
+            }
         }
+        .boxed()
     }
 }
 
-struct LightNetworkMessageHandler;
+/// 收到任何 JSON 即视为在线，`NetworkReport` 只在生成回调内容时严格解析
+struct LightNetworkHandler;
 
-impl MessageHandle for LightNetworkMessageHandler {
-    async fn handle(&self, _topic: &String, device_number: String, payload: String) {
-        // 更新在线状态
-        let manager = get_device_manager();
-        let mut manager = manager.lock().await;
-        manager.update_status(&device_number, true);
-        info!("The device {} is online!", device_number);
+impl TopicHandler for LightNetworkHandler {
+    type Payload = Value;
 
-        // 创建任务
-        let result = UVLampMqttNotifyJob::create(
-            device_number,
-            payload,
-            TaskType::LightStatusTask.to_string(),
-        )
+    fn name(&self) -> &'static str {
+        "light_network"
+    }
+
+    fn handle<'a>(&'a self, message: &'a InboundMessage, _payload: Value) -> BoxFuture<'a, ()> {
+        async move {
+            let device_number = &message.device_number;
+            // 更新在线状态
+            let manager = get_device_manager();
+            let mut manager = manager.lock().await;
+            if manager.update_status(device_number, true) {
+                get_event_bus().publish(LampEvent::online(device_number.clone()));
+            }
+            info!("The device {} is online!", device_number);
+
+            // 创建任务
+            let result = UVLampMqttNotifyJob::create(
+                device_number.clone(),
+                message.payload.clone(),
+                TaskType::LightStatusTask.to_string(),
+                None,
+            )
             .await;
-        match result {
-            Ok(id) => info!("Created light network notification job, id {}", id),
-            Err(e) => error!("An error occurred: {}", e),
+            match result {
+                Ok(id) => info!("Created light network notification job, id {}", id),
+                Err(e) => error!("An error occurred: {}", e),
+            }
         }
+        .boxed()
     }
 }
 
 impl MqttHandler {
+    /// 注册等待设备对指定消息的回复，必须在发送指令前调用，避免回复先于注册到达
+    pub fn wait_for_reply(
+        &self,
+        device_number: &str,
+        message_id: &str,
+    ) -> Result<ReplyWaiter, anyhow::Error> {
+        get_reply_waiters().register(device_number, message_id)
+    }
+
+    /// 与 MQTT 服务器的连接是否可用
+    pub fn is_connected(&self) -> bool {
+        self.connection.is_connected()
+    }
+
+    pub fn connection_status(&self) -> ConnectionStatus {
+        self.connection.status()
+    }
+
     pub async fn send(&self, topic: &str, message: String) -> Result<(), anyhow::Error> {
         self.sender
             .send((topic.to_string(), message))
@@ -96,14 +244,20 @@ impl MqttHandler {
         let mut mqtt_options = MqttOptions::new(client_id, host, port);
         mqtt_options.set_credentials(username, password);
         mqtt_options.set_keep_alive(Duration::from_secs(60));
+        if let Some(tls) = TlsSettings::from_env() {
+            info!(
+                "MQTT TLS enabled, CA file: {:?}, client certificate: {:?}",
+                tls.ca_file, tls.client_cert_file
+            );
+            mqtt_options.set_transport(tls.transport()?);
+        }
 
         let (client, mut event_loop) = AsyncClient::new(mqtt_options, 30);
 
         let (sender, mut receiver) = mpsc::channel(100);
-
-        for topic in SUBSCRIBE_TOPIC {
-            client.subscribe(topic, QoS::AtLeastOnce).await?;
-        }
+        let connection = Arc::new(ConnectionTracker::new(Backoff::from_env()));
+        let event_loop_connection = connection.clone();
+        let router = router();
 
         tokio::spawn(async move {
             loop {
@@ -111,38 +265,66 @@ impl MqttHandler {
                     event = event_loop.poll() => {
                         match event {
                             Ok(Event::Incoming(Incoming::Publish(publish))) => {
-                                Self::handle_received_message(publish).await;
+                                Self::handle_received_message(&router, publish).await;
+                            },
+                            Ok(Event::Incoming(Incoming::ConnAck(ack))) => {
+                                info!("MQTT connected: {:?}", ack.code);
+                                event_loop_connection.on_connected();
+                                // clean session 下断线重连后订阅不会保留，每次连接成功都重新订阅
+                                Self::subscribe(&client);
+                            },
+                            Ok(Event::Incoming(Incoming::SubAck(ack))) => {
+                                if ack.return_codes.contains(&SubscribeReasonCode::Failure) {
+                                    error!("MQTT subscribe rejected: {:?}", ack.return_codes);
+                                } else {
+                                    info!("MQTT subscribed: {:?}", ack.return_codes);
+                                }
                             },
                             Ok(notif) => debug!("MQTT Event: {:?}", notif),
                             Err(e) => {
-                                eprintln!("MQTT Event: {:?}", e);
+                                let delay = event_loop_connection.on_error(e.to_string());
+                                warn!(
+                                    "MQTT connection error: {}, reconnecting in {}s (attempt {})",
+                                    e,
+                                    delay.as_secs(),
+                                    event_loop_connection.status().reconnect_attempts
+                                );
+                                // 下一次 poll 会重新连接
+                                tokio::time::sleep(delay).await;
                             }
                         }
                     },
                     Some((topic, message)) = receiver.recv() => {
-                        match client.publish(topic, QoS::AtLeastOnce, false, message).await {
-                            Ok(_) => info!("Topic message sent successfully!"),
-                            Err(e) => error!("Topic message error: {}", e.to_string()),
+                        // 不能在事件循环所在的任务中等待请求队列，队列满时直接失败
+                        match client.try_publish(topic, QoS::AtLeastOnce, false, message) {
+                            Ok(_) => {
+                                counter!(MQTT_PUBLISHES_TOTAL, "result" => "sent").increment(1);
+                                info!("Topic message sent successfully!")
+                            }
+                            Err(e) => {
+                                counter!(MQTT_PUBLISHES_TOTAL, "result" => "failed").increment(1);
+                                error!("Topic message error: {}", e.to_string())
+                            }
                         }
                     }
                 }
             }
         });
 
-        Ok(MqttHandler { sender })
+        Ok(MqttHandler { sender, connection })
     }
 
-    fn parse_topic(topic: &String) -> Option<Message> {
-        if topic.contains("up/c") {
-            Some(Message::LightSwitchResponse(topic.to_string()))
-        } else if topic.contains("nI/c") {
-            Some(Message::LightNetworkResponse(topic.to_string()))
-        } else {
-            None
+    fn subscribe(client: &AsyncClient) {
+        let filters = get_topic_config()
+            .subscriptions()
+            .into_iter()
+            .map(|topic| SubscribeFilter::new(topic, QoS::AtLeastOnce));
+        if let Err(e) = client.try_subscribe_many(filters) {
+            error!("MQTT subscribe failed: {}", e);
         }
     }
 
-    async fn handle_received_message(publish: Publish) {
+    async fn handle_received_message(router: &TopicRouter, publish: Publish) {
         let topic = publish.topic;
         let payload = publish.payload.to_vec();
         let payload = match String::from_utf8(payload) {
@@ -157,51 +339,67 @@ impl MqttHandler {
             return;
         }
         info!("Received message: topic [{}], payload: {}", topic, payload);
-        let device_number = match get_device_number_from_topic(topic.as_str()) {
-            Some(device_number) => device_number,
-            None => return,
-        };
-        save_received_message(&topic, &device_number, &payload).await;
-        if let Some(message) = Self::parse_topic(&topic) {
-            match message {
-                Message::LightSwitchResponse(message) if message.contains("up/c") => {
-                    LightSwitchMessageHandler
-                        .handle(&topic, device_number, payload)
-                        .await;
-                }
-                Message::LightNetworkResponse(message) if message.contains("nI/c") => {
-                    LightNetworkMessageHandler
-                        .handle(&topic, device_number, payload)
-                        .await;
-                }
-                _ => {}
+        let parsed = get_topic_config().parse(&topic);
+        let kind = parsed
+            .as_ref()
+            .and_then(|parsed| TopicKind::from_channel(&parsed.channel));
+        let label = kind.map_or("other", |kind| kind.as_segment());
+        counter!(MQTT_MESSAGES_RECEIVED_TOTAL, "kind" => label).increment(1);
+        let parsed = match parsed {
+            Some(parsed) => parsed,
+            None => {
+                error!("Received message: unrecognized topic {}", topic);
+                return;
             }
-        }
+        };
+        save_received_message(&topic, kind, &parsed.device_number, &payload).await;
+
+        // 回复处理期间的日志和由回复创建的通知任务都带上对应指令的请求 ID
+        let request_id = find_reply_request_id(&parsed.device_number, &payload).await;
+        let span = info_span!("mqtt_reply", request_id = request_id.as_deref());
+        let message = InboundMessage {
+            topic,
+            product_key: parsed.product_key,
+            device_number: parsed.device_number,
+            channel: parsed.channel,
+            payload,
+        };
+        request_id::scope(request_id, router.dispatch(&message))
+            .instrument(span)
+            .await;
     }
 }
 
-async fn save_received_message(topic: &String, device_number: &String, payload: &String) {
+async fn save_received_message(
+    topic: &str,
+    kind: Option<TopicKind>,
+    device_number: &str,
+    payload: &str,
+) {
     let result =
-        UVLampMqttReceivedMessages::create(topic, device_number.to_string(), payload).await;
+        UVLampMqttReceivedMessages::create(topic, kind, device_number.to_string(), payload).await;
     match result {
         Ok(id) => info!("Saved message, id {}", id),
         Err(e) => error!("An error occurred: {}", e),
     }
 }
 
-fn get_device_number_from_topic(topic: &str) -> Option<String> {
-    let parts: Vec<String> = topic.split("/").map(|s| s.to_string()).collect();
-    if let Some(device_number) = parts.get(1) {
-        Some(device_number.clone())
-    } else {
-        error!("Received message: not found device number!");
-        None
+/// 回复对应指令的请求 ID
+async fn find_reply_request_id(device_number: &str, payload: &str) -> Option<String> {
+    let message_id = protocol::common::message_id(payload)?.to_string();
+    match UVLampMqttMessage::find_request_id(&message_id, device_number).await {
+        Ok(request_id) => request_id,
+        Err(e) => {
+            error!("Failed to find request id of message {}: {}", message_id, e);
+            None
+        }
     }
 }
 
 static MQTT_HANDLER: OnceCell<Arc<MqttHandler>> = OnceCell::new();
 
 pub async fn init_mqtt_handler() -> Result<(), anyhow::Error> {
+    init_topic_config()?;
     let handler = MqttHandler::new().await?;
     MQTT_HANDLER
         .set(Arc::new(handler))
@@ -217,6 +415,22 @@ pub struct DeviceInfo {
     is_online: bool,
     last_response_time: Option<u64>,
     last_query_time: Option<u64>,
+    // 最近一次被判定为离线的时间，离线检查据此间隔重复通知
+    last_offline_time: Option<u64>,
+}
+
+impl DeviceInfo {
+    pub fn is_online(&self) -> bool {
+        self.is_online
+    }
+
+    pub fn last_response_time(&self) -> Option<u64> {
+        self.last_response_time
+    }
+
+    pub fn last_query_time(&self) -> Option<u64> {
+        self.last_query_time
+    }
 }
 
 pub struct DeviceManager {
@@ -241,30 +455,66 @@ impl DeviceManager {
                     is_online: false,
                     last_response_time: None,
                     last_query_time: Some(current_time),
+                    last_offline_time: None,
                 });
         }
     }
 
-    pub fn update_status(&mut self, device_number: &String, is_online: bool) {
+    /// 更新在线状态，返回在线状态是否发生了变化。
+    /// 只有收到设备消息(`is_online` 为 true)时才更新最后响应时间
+    pub fn update_status(&mut self, device_number: &String, is_online: bool) -> bool {
         let current_time = Self::get_current_time();
         if let Some(device_info) = self.devices.get_mut(device_number) {
+            let changed = device_info.is_online != is_online;
             device_info.is_online = is_online;
-            device_info.last_response_time = Some(current_time);
+            if is_online {
+                device_info.last_response_time = Some(current_time);
+            } else {
+                device_info.last_offline_time = Some(current_time);
+            }
+            changed
         } else {
             self.devices.insert(
                 device_number.clone(),
                 DeviceInfo {
                     is_online,
-                    last_response_time: Some(current_time),
+                    last_response_time: is_online.then_some(current_time),
                     last_query_time: None,
+                    last_offline_time: (!is_online).then_some(current_time),
                 });
+            is_online
         }
     }
 
+    pub fn get_device(&self, device_number: &str) -> Option<&DeviceInfo> {
+        self.devices.get(device_number)
+    }
+
+    pub fn remove_device(&mut self, device_number: &str) {
+        self.devices.remove(device_number);
+    }
+
+    /// 只保留设备注册表中在用的设备，已停用或删除的设备不再参与离线检测
+    pub fn retain_devices(&mut self, device_numbers: &[String]) {
+        self.devices
+            .retain(|device_number, _| device_numbers.contains(device_number));
+    }
+
+    /// 按在线状态统计设备数，返回 (在线, 离线)
+    pub fn count_by_status(&self) -> (usize, usize) {
+        let online = self
+            .devices
+            .values()
+            .filter(|device_info| device_info.is_online)
+            .count();
+        (online, self.devices.len() - online)
+    }
+
     pub fn find_all_offline_devices(&self) -> Vec<String> {
         let current_time = Self::get_current_time();
         self.devices.iter().filter_map(|(device_number, device_info)| {
-            if device_info.last_response_time.is_none() || current_time - device_info.last_response_time.unwrap() > 180 {
+            let last_seen_time = device_info.last_response_time.max(device_info.last_offline_time);
+            if last_seen_time.is_none() || current_time - last_seen_time.unwrap() > 180 {
                 Some(device_number.clone())
             } else {
                 None
@@ -284,4 +534,140 @@ static DEVICE_MANAGER: OnceCell<Arc<Mutex<DeviceManager>>> = OnceCell::new();
 
 pub fn get_device_manager() -> &'static Arc<Mutex<DeviceManager>> {
     DEVICE_MANAGER.get_or_init(|| Arc::new(Mutex::new(DeviceManager::new())))
-}
\ No newline at end of file
+}
+
+type ReplyKey = (String, String);
+
+/// 正在等待设备回复的指令，按设备编号和消息 ID 匹配
+pub struct ReplyWaiters {
+    // 每次注册分配一个序号，避免已回复的等待者在释放时移除同一指令后来的注册
+    waiters: std::sync::Mutex<HashMap<ReplyKey, (u64, oneshot::Sender<String>)>>,
+    next_token: AtomicU64,
+}
+
+pub struct ReplyWaiter {
+    key: ReplyKey,
+    token: u64,
+    receiver: oneshot::Receiver<String>,
+}
+
+impl ReplyWaiters {
+    fn new() -> Self {
+        ReplyWaiters {
+            waiters: std::sync::Mutex::new(HashMap::new()),
+            next_token: AtomicU64::new(0),
+        }
+    }
+
+    fn register(&self, device_number: &str, message_id: &str) -> Result<ReplyWaiter, anyhow::Error> {
+        let key = (device_number.to_string(), message_id.to_string());
+        let mut waiters = self.waiters.lock().expect("reply waiters poisoned");
+        if waiters.contains_key(&key) {
+            return Err(anyhow!(
+                "Already waiting for reply of message {} from device {}",
+                message_id,
+                device_number
+            ));
+        }
+        let (sender, receiver) = oneshot::channel();
+        let token = self.next_token.fetch_add(1, Ordering::Relaxed);
+        waiters.insert(key.clone(), (token, sender));
+        Ok(ReplyWaiter {
+            key,
+            token,
+            receiver,
+        })
+    }
+
+    fn resolve(&self, device_number: &str, message_id: &str, payload: &str) {
+        let key = (device_number.to_string(), message_id.to_string());
+        let sender = self
+            .waiters
+            .lock()
+            .expect("reply waiters poisoned")
+            .remove(&key);
+        if let Some((_, sender)) = sender {
+            if sender.send(payload.to_string()).is_err() {
+                debug!("Reply waiter of message {} is gone", key.1);
+            }
+        }
+    }
+
+    fn remove(&self, key: &ReplyKey, token: u64) {
+        let mut waiters = self.waiters.lock().expect("reply waiters poisoned");
+        if waiters.get(key).is_some_and(|(current, _)| *current == token) {
+            waiters.remove(key);
+        }
+    }
+}
+
+impl ReplyWaiter {
+    /// 等待设备回复的原始 payload，超时返回 `None`
+    pub async fn wait(mut self, timeout: Duration) -> Option<String> {
+        tokio::time::timeout(timeout, &mut self.receiver)
+            .await
+            .ok()?
+            .ok()
+    }
+}
+
+impl Drop for ReplyWaiter {
+    fn drop(&mut self) {
+        get_reply_waiters().remove(&self.key, self.token);
+    }
+}
+
+static REPLY_WAITERS: OnceCell<ReplyWaiters> = OnceCell::new();
+
+fn get_reply_waiters() -> &'static ReplyWaiters {
+    REPLY_WAITERS.get_or_init(ReplyWaiters::new)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{get_reply_waiters, DeviceManager};
+    use std::time::Duration;
+
+    #[test]
+    fn test_update_status_keeps_last_response_time() {
+        let mut manager = DeviceManager::new();
+        let device_number = "867255071234567".to_string();
+        assert!(manager.update_status(&device_number, true));
+
+        let last_response_time = DeviceManager::get_current_time() - 200;
+        manager.devices.get_mut(&device_number).unwrap().last_response_time =
+            Some(last_response_time);
+        assert_eq!(manager.find_all_offline_devices(), vec![device_number.clone()]);
+
+        // 判定离线不是收到消息，最后响应时间保持不变，下一次离线检查间隔后再通知
+        assert!(manager.update_status(&device_number, false));
+        let device = manager.get_device(&device_number).unwrap();
+        assert!(!device.is_online());
+        assert_eq!(device.last_response_time(), Some(last_response_time));
+        assert!(manager.find_all_offline_devices().is_empty());
+
+        assert!(manager.update_status(&device_number, true));
+        let device = manager.get_device(&device_number).unwrap();
+        assert!(device.last_response_time().unwrap() > last_response_time);
+    }
+
+    #[tokio::test]
+    async fn test_reply_waiter_drop_after_reregister() {
+        let waiters = get_reply_waiters();
+        let first = waiters.register("test-device", "100001").unwrap();
+        waiters.resolve("test-device", "100001", "first");
+
+        // 第一个等待者释放前，同一指令重新注册
+        let second = waiters.register("test-device", "100001").unwrap();
+        assert_eq!(
+            first.wait(Duration::from_millis(10)).await.as_deref(),
+            Some("first")
+        );
+
+        waiters.resolve("test-device", "100001", "second");
+        assert_eq!(
+            second.wait(Duration::from_millis(10)).await.as_deref(),
+            Some("second")
+        );
+    }
+}
@@ -0,0 +1,151 @@
+use serde::Serialize;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ConnectionState {
+    /// 启动后尚未收到 ConnAck
+    Connecting,
+    Connected,
+    /// 连接断开，等待重连
+    Disconnected,
+}
+
+#[derive(Debug, Clone)]
+pub struct ConnectionStatus {
+    pub state: ConnectionState,
+    /// 进入当前状态的时间戳(秒)
+    pub since: u64,
+    /// 连续重连失败的次数，连接成功后清零
+    pub reconnect_attempts: u32,
+    pub last_error: Option<String>,
+    pub last_connected_at: Option<u64>,
+}
+
+/// 重连等待时间按失败次数指数增长，不超过 `max`
+#[derive(Debug, Clone, Copy)]
+pub struct Backoff {
+    pub min: Duration,
+    pub max: Duration,
+}
+
+impl Backoff {
+    pub fn from_env() -> Self {
+        let read = |key: &str, default: u64| {
+            std::env::var(key)
+                .ok()
+                .and_then(|value| value.parse::<u64>().ok())
+                .unwrap_or(default)
+        };
+        let min = Duration::from_secs(read("UV_LAMP_MQTT_RECONNECT_MIN_DELAY", 1).max(1));
+        let max = Duration::from_secs(read("UV_LAMP_MQTT_RECONNECT_MAX_DELAY", 60));
+        Backoff {
+            min,
+            max: max.max(min),
+        }
+    }
+
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(16);
+        self.min.saturating_mul(1 << exponent).min(self.max)
+    }
+}
+
+/// MQTT 连接状态，由事件循环更新，供接口和就绪检查查询
+pub struct ConnectionTracker {
+    status: Mutex<ConnectionStatus>,
+    backoff: Backoff,
+}
+
+impl ConnectionTracker {
+    pub fn new(backoff: Backoff) -> Self {
+        ConnectionTracker {
+            status: Mutex::new(ConnectionStatus {
+                state: ConnectionState::Connecting,
+                since: now(),
+                reconnect_attempts: 0,
+                last_error: None,
+                last_connected_at: None,
+            }),
+            backoff,
+        }
+    }
+
+    pub fn status(&self) -> ConnectionStatus {
+        self.status.lock().expect("mqtt connection status poisoned").clone()
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.status().state == ConnectionState::Connected
+    }
+
+    pub fn on_connected(&self) {
+        let mut status = self.status.lock().expect("mqtt connection status poisoned");
+        let now = now();
+        status.state = ConnectionState::Connected;
+        status.since = now;
+        status.reconnect_attempts = 0;
+        status.last_connected_at = Some(now);
+    }
+
+    /// 记录连接错误，返回下次重连前需要等待的时间
+    pub fn on_error(&self, error: String) -> Duration {
+        let mut status = self.status.lock().expect("mqtt connection status poisoned");
+        if status.state != ConnectionState::Disconnected {
+            status.state = ConnectionState::Disconnected;
+            status.since = now();
+        }
+        status.reconnect_attempts = status.reconnect_attempts.saturating_add(1);
+        status.last_error = Some(error);
+        self.backoff.delay(status.reconnect_attempts)
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Time went backwards")
+        .as_secs()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Backoff, ConnectionState, ConnectionTracker};
+    use std::time::Duration;
+
+    #[test]
+    fn test_backoff_delay() {
+        let backoff = Backoff {
+            min: Duration::from_secs(1),
+            max: Duration::from_secs(30),
+        };
+        assert_eq!(backoff.delay(1), Duration::from_secs(1));
+        assert_eq!(backoff.delay(2), Duration::from_secs(2));
+        assert_eq!(backoff.delay(5), Duration::from_secs(16));
+        assert_eq!(backoff.delay(6), Duration::from_secs(30));
+        assert_eq!(backoff.delay(u32::MAX), Duration::from_secs(30));
+    }
+
+    #[test]
+    fn test_connection_tracker() {
+        let tracker = ConnectionTracker::new(Backoff {
+            min: Duration::from_secs(1),
+            max: Duration::from_secs(60),
+        });
+        assert_eq!(tracker.status().state, ConnectionState::Connecting);
+
+        assert_eq!(tracker.on_error("refused".to_string()), Duration::from_secs(1));
+        assert_eq!(tracker.on_error("refused".to_string()), Duration::from_secs(2));
+        let status = tracker.status();
+        assert_eq!(status.state, ConnectionState::Disconnected);
+        assert_eq!(status.reconnect_attempts, 2);
+        assert_eq!(status.last_error.as_deref(), Some("refused"));
+
+        tracker.on_connected();
+        assert!(tracker.is_connected());
+        assert_eq!(tracker.status().reconnect_attempts, 0);
+        assert!(tracker.status().last_connected_at.is_some());
+    }
+}
@@ -0,0 +1,206 @@
+use futures::future::BoxFuture;
+use serde::de::DeserializeOwned;
+use tracing::{debug, warn};
+
+/// 收到的设备消息，主题已按模板解析
+#[derive(Debug, Clone)]
+pub struct InboundMessage {
+    pub topic: String,
+    pub product_key: String,
+    pub device_number: String,
+    pub channel: String,
+    /// 原始 payload，需要原样保存时使用
+    pub payload: String,
+}
+
+/// 某类主题消息的处理器，payload 解析为 `Payload` 后才会调用，解析失败时跳过
+pub trait TopicHandler: Send + Sync + 'static {
+    type Payload: DeserializeOwned + Send;
+
+    fn name(&self) -> &'static str;
+
+    fn handle<'a>(
+        &'a self,
+        message: &'a InboundMessage,
+        payload: Self::Payload,
+    ) -> BoxFuture<'a, ()>;
+}
+
+// 擦除 payload 类型，使不同的处理器可以放在同一个路由表中
+trait ErasedHandler: Send + Sync {
+    fn dispatch<'a>(&'a self, message: &'a InboundMessage) -> Option<BoxFuture<'a, ()>>;
+}
+
+impl<H: TopicHandler> ErasedHandler for H {
+    fn dispatch<'a>(&'a self, message: &'a InboundMessage) -> Option<BoxFuture<'a, ()>> {
+        match serde_json::from_str::<H::Payload>(&message.payload) {
+            Ok(payload) => Some(self.handle(message, payload)),
+            Err(e) => {
+                warn!(
+                    "Skip handler {} for topic {}: invalid payload: {}",
+                    self.name(),
+                    message.topic,
+                    e
+                );
+                None
+            }
+        }
+    }
+}
+
+struct Route {
+    filter: String,
+    handler: Box<dyn ErasedHandler>,
+}
+
+/// 按 MQTT 主题过滤器分发消息，同一主题可以有多个处理器，按注册顺序依次执行
+#[derive(Default)]
+pub struct TopicRouter {
+    routes: Vec<Route>,
+}
+
+impl TopicRouter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn route<H: TopicHandler>(mut self, filter: impl Into<String>, handler: H) -> Self {
+        self.routes.push(Route {
+            filter: filter.into(),
+            handler: Box::new(handler),
+        });
+        self
+    }
+
+    /// 返回实际执行的处理器个数
+    pub async fn dispatch(&self, message: &InboundMessage) -> usize {
+        let mut handled = 0;
+        for route in &self.routes {
+            if !topic_matches(&route.filter, &message.topic) {
+                continue;
+            }
+            if let Some(future) = route.handler.dispatch(message) {
+                future.await;
+                handled += 1;
+            }
+        }
+        if handled == 0 {
+            debug!("No handler for topic {}", message.topic);
+        }
+        handled
+    }
+}
+
+/// 按 MQTT 规则匹配主题过滤器，`+` 匹配一段，`#` 匹配剩余的所有段
+pub fn topic_matches(filter: &str, topic: &str) -> bool {
+    let mut topic_levels = topic.split('/');
+    for filter_level in filter.split('/') {
+        if filter_level == "#" {
+            return true;
+        }
+        match topic_levels.next() {
+            Some(level) if filter_level == "+" || filter_level == level => {}
+            _ => return false,
+        }
+    }
+    topic_levels.next().is_none()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{topic_matches, InboundMessage, TopicHandler, TopicRouter};
+    use futures::future::BoxFuture;
+    use futures::FutureExt;
+    use serde::Deserialize;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn test_topic_matches() {
+        assert!(topic_matches("+/+/up/c", "pk/0001/up/c"));
+        assert!(topic_matches("pk/#", "pk/0001/up/c"));
+        assert!(topic_matches("#", "pk/0001/up/c"));
+        assert!(!topic_matches("+/+/up/c", "pk/0001/oc/c"));
+        assert!(!topic_matches("+/+/up", "pk/0001/up/c"));
+        assert!(!topic_matches("+/+/up/c/+", "pk/0001/up/c"));
+    }
+
+    #[derive(Deserialize)]
+    struct IdPayload {
+        id: u32,
+    }
+
+    struct Recorder {
+        name: &'static str,
+        calls: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl TopicHandler for Recorder {
+        type Payload = IdPayload;
+
+        fn name(&self) -> &'static str {
+            self.name
+        }
+
+        fn handle<'a>(
+            &'a self,
+            message: &'a InboundMessage,
+            payload: IdPayload,
+        ) -> BoxFuture<'a, ()> {
+            async move {
+                self.calls.lock().unwrap().push(format!(
+                    "{}:{}:{}",
+                    self.name, message.device_number, payload.id
+                ));
+            }
+            .boxed()
+        }
+    }
+
+    fn message(topic: &str, payload: &str) -> InboundMessage {
+        InboundMessage {
+            topic: topic.to_string(),
+            product_key: "pk".to_string(),
+            device_number: "0001".to_string(),
+            channel: "up/c".to_string(),
+            payload: payload.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dispatch() {
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let recorder = |name| Recorder {
+            name,
+            calls: calls.clone(),
+        };
+        let router = TopicRouter::new()
+            .route("+/+/up/c", recorder("first"))
+            .route("+/+/oc/c", recorder("ack"))
+            .route("+/+/up/c", recorder("second"));
+
+        assert_eq!(
+            router
+                .dispatch(&message("pk/0001/up/c", r#"{"id":1}"#))
+                .await,
+            2
+        );
+        assert_eq!(
+            *calls.lock().unwrap(),
+            vec!["first:0001:1", "second:0001:1"]
+        );
+
+        // payload 不符合类型时不调用处理器
+        assert_eq!(
+            router
+                .dispatch(&message("pk/0001/up/c", r#"{"s":1}"#))
+                .await,
+            0
+        );
+        assert_eq!(
+            router
+                .dispatch(&message("pk/0001/nI/c", r#"{"id":1}"#))
+                .await,
+            0
+        );
+    }
+}
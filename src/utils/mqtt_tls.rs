@@ -0,0 +1,172 @@
+use anyhow::{anyhow, Context};
+use rumqttc::tokio_rustls::rustls;
+use rumqttc::tokio_rustls::rustls::client::danger::{
+    HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier,
+};
+use rumqttc::tokio_rustls::rustls::crypto::{
+    ring, verify_tls12_signature, verify_tls13_signature, WebPkiSupportedAlgorithms,
+};
+use rumqttc::tokio_rustls::rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use rumqttc::tokio_rustls::rustls::{ClientConfig, DigitallySignedStruct, SignatureScheme};
+use rumqttc::{TlsConfiguration, Transport};
+use std::sync::Arc;
+use tracing::warn;
+
+/// MQTT 的 TLS 配置，`UV_LAMP_MQTT_TLS` 未开启时使用明文 TCP
+#[derive(Debug, Default)]
+pub struct TlsSettings {
+    /// PEM 格式的 CA 证书，不指定时使用系统证书
+    pub ca_file: Option<String>,
+    /// PEM 格式的客户端证书和私钥，双向认证时需要同时指定
+    pub client_cert_file: Option<String>,
+    pub client_key_file: Option<String>,
+    /// 不校验服务端证书，只用于本地测试
+    pub insecure: bool,
+}
+
+impl TlsSettings {
+    pub fn from_env() -> Option<Self> {
+        if !read_bool("UV_LAMP_MQTT_TLS") {
+            return None;
+        }
+        let read = |key: &str| std::env::var(key).ok().filter(|value| !value.trim().is_empty());
+        Some(TlsSettings {
+            ca_file: read("UV_LAMP_MQTT_CA_FILE"),
+            client_cert_file: read("UV_LAMP_MQTT_CLIENT_CERT_FILE"),
+            client_key_file: read("UV_LAMP_MQTT_CLIENT_KEY_FILE"),
+            insecure: read_bool("UV_LAMP_MQTT_TLS_INSECURE"),
+        })
+    }
+
+    pub fn transport(&self) -> Result<Transport, anyhow::Error> {
+        let client_auth = match (&self.client_cert_file, &self.client_key_file) {
+            (Some(cert_file), Some(key_file)) => Some((read_file(cert_file)?, read_file(key_file)?)),
+            (None, None) => None,
+            _ => {
+                return Err(anyhow!(
+                    "UV_LAMP_MQTT_CLIENT_CERT_FILE and UV_LAMP_MQTT_CLIENT_KEY_FILE must be set together"
+                ))
+            }
+        };
+
+        if self.insecure {
+            warn!("MQTT TLS certificate verification is disabled");
+            let config = insecure_client_config(client_auth)?;
+            return Ok(Transport::tls_with_config(TlsConfiguration::Rustls(Arc::new(config))));
+        }
+
+        match &self.ca_file {
+            Some(ca_file) => Ok(Transport::tls_with_config(TlsConfiguration::Simple {
+                ca: read_file(ca_file)?,
+                alpn: None,
+                client_auth,
+            })),
+            // 系统证书的默认配置不支持客户端证书
+            None if client_auth.is_some() => Err(anyhow!(
+                "UV_LAMP_MQTT_CA_FILE is required when using a client certificate"
+            )),
+            None => Ok(Transport::tls_with_config(TlsConfiguration::default())),
+        }
+    }
+}
+
+fn read_bool(key: &str) -> bool {
+    std::env::var(key)
+        .map(|value| matches!(value.trim().to_lowercase().as_str(), "1" | "true" | "yes"))
+        .unwrap_or(false)
+}
+
+fn read_file(path: &str) -> Result<Vec<u8>, anyhow::Error> {
+    std::fs::read(path).with_context(|| format!("Failed to read {}", path))
+}
+
+fn insecure_client_config(
+    client_auth: Option<(Vec<u8>, Vec<u8>)>,
+) -> Result<ClientConfig, anyhow::Error> {
+    let builder = ClientConfig::builder()
+        .dangerous()
+        .with_custom_certificate_verifier(Arc::new(NoCertificateVerification {
+            algorithms: ring::default_provider().signature_verification_algorithms,
+        }));
+    let config = match client_auth {
+        Some((cert, key)) => {
+            let certs = rustls_pemfile::certs(&mut cert.as_slice())
+                .collect::<Result<Vec<_>, _>>()
+                .context("Invalid client certificate")?;
+            let key = rustls_pemfile::private_key(&mut key.as_slice())
+                .context("Invalid client key")?
+                .ok_or_else(|| anyhow!("No private key found in client key file"))?;
+            builder.with_client_auth_cert(certs, key)?
+        }
+        None => builder.with_no_client_auth(),
+    };
+    Ok(config)
+}
+
+/// 接受任何服务端证书，但仍校验握手签名
+#[derive(Debug)]
+struct NoCertificateVerification {
+    algorithms: WebPkiSupportedAlgorithms,
+}
+
+impl ServerCertVerifier for NoCertificateVerification {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls12_signature(message, cert, dss, &self.algorithms)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls13_signature(message, cert, dss, &self.algorithms)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.algorithms.supported_schemes()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::TlsSettings;
+    use rumqttc::Transport;
+
+    #[test]
+    fn test_transport() {
+        let settings = TlsSettings {
+            insecure: true,
+            ..Default::default()
+        };
+        assert!(matches!(settings.transport(), Ok(Transport::Tls(_))));
+
+        let settings = TlsSettings {
+            client_cert_file: Some("client.crt".to_string()),
+            ..Default::default()
+        };
+        assert!(settings.transport().is_err());
+
+        let settings = TlsSettings {
+            ca_file: Some("/nonexistent/ca.crt".to_string()),
+            ..Default::default()
+        };
+        assert!(settings.transport().is_err());
+    }
+}
@@ -25,4 +25,10 @@ impl MySql {
             })
             .await
     }
+
+    /// 从连接池取一个连接执行 `SELECT 1`，用于就绪检查
+    pub async fn ping(&self) -> Result<(), Error> {
+        sqlx::query("SELECT 1").execute(&self.pool).await?;
+        Ok(())
+    }
 }
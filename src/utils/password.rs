@@ -12,7 +12,7 @@ pub fn hash_password(password: &str) -> Result<String, argon2::password_hash::Er
 
 pub fn verify_password(password: &str, password_hash: &str) -> Result<bool, anyhow::Error> {
     let config = Argon2::default();
-    let password_hash = PasswordHash::new(&password_hash).map_err(|e| anyhow::anyhow!(e))?;
+    let password_hash = PasswordHash::new(password_hash).map_err(|e| anyhow::anyhow!(e))?;
     let pass = config
         .verify_password(password.as_bytes(), &password_hash)
         .is_ok();
@@ -31,6 +31,7 @@ mod test {
     }
 
     #[test]
+    #[allow(clippy::bool_assert_comparison)]
     fn test_verify_password() {
         let password = "password";
         let hash = hash_password(password).unwrap();
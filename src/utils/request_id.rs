@@ -0,0 +1,62 @@
+use std::future::Future;
+
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+// 外部传入的请求 ID 最大长度，与数据库字段长度一致
+const MAX_LENGTH: usize = 64;
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+/// 当前请求的 ID，不在请求上下文中(如定时任务)时返回 `None`
+pub fn current() -> Option<String> {
+    REQUEST_ID.try_with(|request_id| request_id.clone()).ok()
+}
+
+/// 在指定请求 ID 的上下文中执行 `future`，用于把请求 ID 带入 `tokio::spawn` 的任务
+pub async fn scope<F>(request_id: Option<String>, future: F) -> F::Output
+where
+    F: Future,
+{
+    match request_id {
+        Some(request_id) => REQUEST_ID.scope(request_id, future).await,
+        None => future.await,
+    }
+}
+
+/// 沿用客户端传入的请求 ID，为空、过长或包含不可见字符时重新生成
+pub fn accept_or_generate(header: Option<&str>) -> String {
+    match header.map(str::trim) {
+        Some(request_id)
+            if !request_id.is_empty()
+                && request_id.len() <= MAX_LENGTH
+                && request_id.chars().all(|c| c.is_ascii_graphic()) =>
+        {
+            request_id.to_string()
+        }
+        _ => uuid::Uuid::new_v4().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{accept_or_generate, current, scope};
+
+    #[test]
+    fn test_accept_or_generate() {
+        assert_eq!(accept_or_generate(Some(" abc-123 ")), "abc-123");
+        assert_eq!(accept_or_generate(None).len(), 36);
+        assert_eq!(accept_or_generate(Some("")).len(), 36);
+        assert_eq!(accept_or_generate(Some("a b")).len(), 36);
+        assert_eq!(accept_or_generate(Some(&"a".repeat(65))).len(), 36);
+    }
+
+    #[tokio::test]
+    async fn test_scope() {
+        assert_eq!(current(), None);
+        let request_id = scope(Some("abc".to_string()), async { current() }).await;
+        assert_eq!(request_id.as_deref(), Some("abc"));
+        assert_eq!(scope(None, async { current() }).await, None);
+    }
+}
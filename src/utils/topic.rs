@@ -0,0 +1,329 @@
+use anyhow::anyhow;
+use once_cell::sync::OnceCell;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// 下发开关指令
+pub const CHANNEL_COMMAND: &str = "oc/s";
+/// 设备确认指令
+pub const CHANNEL_COMMAND_ACK: &str = "oc/c";
+/// 设备上报开关灯状态
+pub const CHANNEL_STATUS_REPORT: &str = "up/c";
+/// 查询设备网络状态
+pub const CHANNEL_NETWORK_QUERY: &str = "nI/s";
+/// 设备上报网络状态
+pub const CHANNEL_NETWORK_REPORT: &str = "nI/c";
+
+const SUBSCRIBE_CHANNELS: [&str; 3] = [
+    CHANNEL_COMMAND_ACK,
+    CHANNEL_STATUS_REPORT,
+    CHANNEL_NETWORK_REPORT,
+];
+
+/// 设备消息的主题类型，对应通道的第一段
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize, ToSchema)]
+pub enum TopicKind {
+    /// 指令确认
+    #[serde(rename = "oc")]
+    Command,
+    /// 开关灯状态上报
+    #[serde(rename = "up")]
+    Status,
+    /// 红外报警等网络消息
+    #[serde(rename = "nI")]
+    Network,
+}
+
+impl TopicKind {
+    /// 通道中对应的类型段
+    pub fn as_segment(&self) -> &'static str {
+        match self {
+            TopicKind::Command => "oc",
+            TopicKind::Status => "up",
+            TopicKind::Network => "nI",
+        }
+    }
+
+    pub fn from_segment(segment: &str) -> Option<Self> {
+        match segment {
+            "oc" => Some(TopicKind::Command),
+            "up" => Some(TopicKind::Status),
+            "nI" => Some(TopicKind::Network),
+            _ => None,
+        }
+    }
+
+    pub fn from_channel(channel: &str) -> Option<Self> {
+        Self::from_segment(channel.split('/').next()?)
+    }
+}
+
+const DEFAULT_TEMPLATE: &str = "{product_key}/{device_number}/{channel}";
+const DEFAULT_PRODUCT_KEY: &str = "87855294541367dab3e244c2441c5f22";
+
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Literal(String),
+    ProductKey,
+    DeviceNumber,
+    // 通道可以包含多段，如 `oc/s`
+    Channel,
+}
+
+/// 主题模板，占位符 `{product_key}`、`{device_number}`、`{channel}` 必须各出现一次且独占一段
+#[derive(Debug, Clone)]
+pub struct TopicTemplate {
+    segments: Vec<Segment>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ParsedTopic {
+    pub product_key: String,
+    pub device_number: String,
+    pub channel: String,
+}
+
+impl TopicTemplate {
+    pub fn parse(template: &str) -> Result<Self, anyhow::Error> {
+        let segments = template
+            .split('/')
+            .map(|segment| match segment {
+                "{product_key}" => Ok(Segment::ProductKey),
+                "{device_number}" => Ok(Segment::DeviceNumber),
+                "{channel}" => Ok(Segment::Channel),
+                _ if segment.is_empty() || segment.contains(['{', '}', '+', '#']) => Err(anyhow!(
+                    "Invalid segment `{}` in topic template {}",
+                    segment,
+                    template
+                )),
+                _ => Ok(Segment::Literal(segment.to_string())),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        for placeholder in [Segment::ProductKey, Segment::DeviceNumber, Segment::Channel] {
+            let count = segments
+                .iter()
+                .filter(|segment| **segment == placeholder)
+                .count();
+            if count != 1 {
+                return Err(anyhow!(
+                    "Topic template {} must contain each placeholder exactly once",
+                    template
+                ));
+            }
+        }
+        Ok(TopicTemplate { segments })
+    }
+
+    pub fn render(&self, product_key: &str, device_number: &str, channel: &str) -> String {
+        self.segments
+            .iter()
+            .map(|segment| match segment {
+                Segment::Literal(literal) => literal.as_str(),
+                Segment::ProductKey => product_key,
+                Segment::DeviceNumber => device_number,
+                Segment::Channel => channel,
+            })
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    pub fn parse_topic(&self, topic: &str) -> Option<ParsedTopic> {
+        let parts: Vec<&str> = topic.split('/').collect();
+        if parts.len() < self.segments.len() {
+            return None;
+        }
+        let channel_len = parts.len() + 1 - self.segments.len();
+
+        let mut product_key = None;
+        let mut device_number = None;
+        let mut channel = None;
+        let mut index = 0;
+        for segment in &self.segments {
+            match segment {
+                Segment::Literal(literal) if parts[index] != literal => return None,
+                Segment::Literal(_) => {}
+                Segment::ProductKey => product_key = Some(parts[index]),
+                Segment::DeviceNumber => device_number = Some(parts[index]),
+                Segment::Channel => {
+                    channel = Some(parts[index..index + channel_len].join("/"));
+                    index += channel_len;
+                    continue;
+                }
+            }
+            index += 1;
+        }
+
+        let device_number = device_number.filter(|device_number| !device_number.is_empty())?;
+        Some(ParsedTopic {
+            product_key: product_key?.to_string(),
+            device_number: device_number.to_string(),
+            channel: channel?,
+        })
+    }
+}
+
+/// 主题模板和接入的产品，第一个产品为未指定产品的设备所使用的默认产品
+#[derive(Debug, Clone)]
+pub struct TopicConfig {
+    template: TopicTemplate,
+    product_keys: Vec<String>,
+}
+
+impl TopicConfig {
+    pub fn new(template: &str, product_keys: Vec<String>) -> Result<Self, anyhow::Error> {
+        if product_keys.is_empty() {
+            return Err(anyhow!("At least one product key is required"));
+        }
+        Ok(TopicConfig {
+            template: TopicTemplate::parse(template)?,
+            product_keys,
+        })
+    }
+
+    pub fn from_env() -> Result<Self, anyhow::Error> {
+        let template = std::env::var("UV_LAMP_MQTT_TOPIC_TEMPLATE")
+            .unwrap_or_else(|_| DEFAULT_TEMPLATE.to_string());
+        let product_keys = std::env::var("UV_LAMP_MQTT_PRODUCT_KEYS")
+            .unwrap_or_else(|_| DEFAULT_PRODUCT_KEY.to_string())
+            .split(',')
+            .map(|product_key| product_key.trim().to_string())
+            .filter(|product_key| !product_key.is_empty())
+            .collect();
+        Self::new(&template, product_keys)
+    }
+
+    pub fn product_keys(&self) -> &[String] {
+        &self.product_keys
+    }
+
+    pub fn contains_product_key(&self, product_key: &str) -> bool {
+        self.product_keys.iter().any(|key| key == product_key)
+    }
+
+    /// 设备没有指定产品时使用默认产品
+    pub fn product_key_or_default<'a>(&'a self, product_key: &'a str) -> &'a str {
+        if product_key.is_empty() {
+            &self.product_keys[0]
+        } else {
+            product_key
+        }
+    }
+
+    pub fn topic(&self, product_key: &str, device_number: &str, channel: &str) -> String {
+        self.template.render(
+            self.product_key_or_default(product_key),
+            device_number,
+            channel,
+        )
+    }
+
+    /// 所有产品的设备上报主题，设备编号为通配符
+    pub fn subscriptions(&self) -> Vec<String> {
+        self.product_keys
+            .iter()
+            .flat_map(|product_key| {
+                SUBSCRIBE_CHANNELS
+                    .iter()
+                    .map(move |channel| self.template.render(product_key, "+", channel))
+            })
+            .collect()
+    }
+
+    /// 所有产品、所有设备某个通道的主题过滤器，用于注册消息处理器
+    pub fn filter(&self, channel: &str) -> String {
+        self.template.render("+", "+", channel)
+    }
+
+    /// 解析收到的主题，不属于已接入产品的主题返回 `None`
+    pub fn parse(&self, topic: &str) -> Option<ParsedTopic> {
+        self.template
+            .parse_topic(topic)
+            .filter(|parsed| self.contains_product_key(&parsed.product_key))
+    }
+}
+
+static TOPIC_CONFIG: OnceCell<TopicConfig> = OnceCell::new();
+
+/// 启动时读取并校验主题配置，配置无效时返回错误而不是在首次使用时 panic
+pub fn init_topic_config() -> Result<&'static TopicConfig, anyhow::Error> {
+    TOPIC_CONFIG.get_or_try_init(TopicConfig::from_env)
+}
+
+/// 需要先调用 `init_topic_config`
+pub fn get_topic_config() -> &'static TopicConfig {
+    TOPIC_CONFIG.get_or_init(|| TopicConfig::from_env().expect("Invalid MQTT topic configuration"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        ParsedTopic, TopicConfig, TopicKind, TopicTemplate, CHANNEL_COMMAND,
+        CHANNEL_NETWORK_REPORT, CHANNEL_STATUS_REPORT,
+    };
+
+    #[test]
+    fn test_template() {
+        assert!(TopicTemplate::parse("{product_key}/{device_number}").is_err());
+        assert!(TopicTemplate::parse("{product_key}/{device_number}/{channel}/{channel}").is_err());
+        assert!(TopicTemplate::parse("lamp/+/{product_key}/{device_number}/{channel}").is_err());
+        assert!(TopicTemplate::parse("lamp-{product_key}/{device_number}/{channel}").is_err());
+
+        let template =
+            TopicTemplate::parse("lamp/{product_key}/{channel}/{device_number}").unwrap();
+        let topic = template.render("pk", "0001", CHANNEL_COMMAND);
+        assert_eq!(topic, "lamp/pk/oc/s/0001");
+        assert_eq!(
+            template.parse_topic(&topic),
+            Some(ParsedTopic {
+                product_key: "pk".to_string(),
+                device_number: "0001".to_string(),
+                channel: "oc/s".to_string(),
+            })
+        );
+        assert!(template.parse_topic("other/pk/oc/s/0001").is_none());
+        assert!(template.parse_topic("lamp/pk/0001").is_none());
+    }
+
+    #[test]
+    fn test_multiple_product_keys() {
+        let config = TopicConfig::new(
+            "{product_key}/{device_number}/{channel}",
+            vec!["a".to_string(), "b".to_string()],
+        )
+        .unwrap();
+        assert_eq!(config.topic("", "0001", CHANNEL_COMMAND), "a/0001/oc/s");
+        assert_eq!(config.topic("b", "0001", CHANNEL_COMMAND), "b/0001/oc/s");
+        assert_eq!(config.subscriptions().len(), 6);
+        assert!(config.subscriptions().contains(&"b/+/up/c".to_string()));
+        assert_eq!(config.filter(CHANNEL_STATUS_REPORT), "+/+/up/c");
+
+        let parsed = config.parse("b/0001/up/c").unwrap();
+        assert_eq!(parsed.product_key, "b");
+        assert_eq!(parsed.channel, CHANNEL_STATUS_REPORT);
+        assert!(config.parse("c/0001/up/c").is_none());
+
+        assert!(TopicConfig::new("{product_key}/{device_number}/{channel}", vec![]).is_err());
+    }
+
+    #[test]
+    fn test_topic_kind() {
+        // 通道在第一段时也能识别类型
+        let config = TopicConfig::new(
+            "{channel}/{product_key}/{device_number}",
+            vec!["pk".to_string()],
+        )
+        .unwrap();
+        let parsed = config.parse("nI/c/pk/0001").unwrap();
+        assert_eq!(parsed.channel, CHANNEL_NETWORK_REPORT);
+        assert_eq!(
+            TopicKind::from_channel(&parsed.channel),
+            Some(TopicKind::Network)
+        );
+        assert_eq!(
+            TopicKind::from_channel(CHANNEL_COMMAND),
+            Some(TopicKind::Command)
+        );
+        assert_eq!(TopicKind::from_segment("up"), Some(TopicKind::Status));
+        assert!(TopicKind::from_channel("vI/c").is_none());
+    }
+}